@@ -0,0 +1,173 @@
+// text usage bars for percentage-like fields, e.g. `[#####-----] 52%`.
+use crate::*;
+
+// the partly filled cells of the `blocks` style, from 1/8 to 7/8
+const EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+#[derive(PartialEq)]
+pub enum BarMode {
+    // after the value, e.g. `3.12GiB / 15.54GiB [#####-----] 52%`
+    Append,
+    // instead of the value
+    Replace,
+}
+
+impl BarMode {
+    pub fn from(mode: &str) -> BarMode {
+        match mode {
+            "replace" => BarMode::Replace,
+            _ => BarMode::Append,
+        }
+    }
+}
+
+pub struct Bar {
+    pub mode: BarMode,
+    // the number of cells between the ends
+    pub width: usize,
+    left: String,
+    filled: char,
+    empty: char,
+    right: String,
+    // whether the last filled cell can be partly filled
+    partial: bool,
+    // from these percentages on, the bar gets the second or third color
+    warn: f64,
+    crit: f64,
+    // the colors for below, from `warn` and from `crit` on
+    pub colors: Vec<Color>,
+}
+
+impl Bar {
+    // `ascii` is `[#####-----]`, `blocks` uses Unicode eighth blocks.
+    pub fn new(mode: BarMode, style: &str) -> Bar {
+        let mut bar = Bar {
+            mode,
+            width: 10,
+            left: "[".to_string(),
+            filled: '#',
+            empty: '-',
+            right: "]".to_string(),
+            partial: false,
+            warn: 60_f64,
+            crit: 85_f64,
+            colors: vec![Color::Ansi(2), Color::Ansi(3), Color::Ansi(1)],
+        };
+
+        if style == "blocks" {
+            bar.left = "▕".to_string();
+            bar.filled = '█';
+            bar.empty = ' ';
+            bar.right = "▏".to_string();
+            bar.partial = true;
+        }
+
+        bar
+    }
+
+    // use 4 custom glyphs: the left end, a filled cell,
+    // an empty cell and the right end, e.g. `[=-]`.
+    pub fn set_chars(&mut self, chars: &str) -> Result<()> {
+        let c = chars.chars().collect::<Vec<char>>();
+        ensure!(
+            c.len() == 4,
+            InvalidBarChars {
+                chars: chars.to_string()
+            }
+        );
+
+        self.left = c[0].to_string();
+        self.filled = c[1];
+        self.empty = c[2];
+        self.right = c[3].to_string();
+        self.partial = false;
+
+        Ok(())
+    }
+
+    // set the percentages from which the bar turns to the
+    // second and third color, e.g. `60,85`.
+    pub fn set_thresholds(&mut self, thresholds: &str) -> Result<()> {
+        let values = thresholds
+            .split(',')
+            .map(|t| t.trim().trim_end_matches('%').parse::<f64>().ok())
+            .collect::<Option<Vec<f64>>>();
+
+        match values.as_deref() {
+            Some(&[warn, crit]) if warn <= crit => {
+                self.warn = warn;
+                self.crit = crit;
+                Ok(())
+            }
+            _ => InvalidThresholds {
+                thresholds: thresholds.to_string(),
+            }
+            .fail(),
+        }
+    }
+
+    // the bar for `percent`, followed by the percentage itself,
+    // in the color of the threshold it's past.
+    pub fn render(&self, percent: f64) -> String {
+        let color = if percent >= self.crit {
+            self.colors.get(2)
+        } else if percent >= self.warn {
+            self.colors.get(1)
+        } else {
+            self.colors.first()
+        };
+
+        format!("{} {:.0}%", self.cells(percent, color), percent.clamp(0_f64, 100_f64))
+    }
+
+    // the bar for a progress, e.g. of a song, in the first color.
+    pub fn progress(&self, percent: f64) -> String {
+        self.cells(percent, self.colors.first())
+    }
+
+    fn cells(&self, percent: f64, color: Option<&Color>) -> String {
+        let percent = percent.clamp(0_f64, 100_f64);
+        let cells = percent / 100_f64 * self.width as f64;
+
+        let mut filled;
+        let mut count;
+        if self.partial {
+            count = cells.floor() as usize;
+            filled = self.filled.to_string().repeat(count);
+
+            let eighths = ((cells - count as f64) * 8_f64).round() as usize;
+            if eighths == 8 {
+                filled.push(self.filled);
+                count += 1;
+            } else if eighths > 0 {
+                filled.push(EIGHTHS[eighths - 1]);
+                count += 1;
+            }
+        } else {
+            count = cells.round() as usize;
+            filled = self.filled.to_string().repeat(count);
+        }
+
+        // only the foreground is reset after the filled cells,
+        // so the rest of the value keeps the theme's color
+        if let Some(color) = color {
+            filled = format!("{}{}\x1B[39m", color.escape(), filled);
+        }
+
+        format!(
+            "{}{}{}{}",
+            self.left,
+            filled,
+            self.empty.to_string().repeat(self.width - count.min(self.width)),
+            self.right
+        )
+    }
+
+    // add the bar to a field's value, or replace the value with it.
+    pub fn apply(&self, value: &str, percent: f64) -> String {
+        match self.mode {
+            BarMode::Append => format!("{} {}", value, self.render(percent)),
+            BarMode::Replace => self.render(percent),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+// terminal colors and themes, as used by the logos and the output styles.
+use crate::*;
+use std::collections::HashMap;
+
+const E: char = 0x1B as char;
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum Color {
+    // one of the 16 standard colors (0-7 normal, 8-15 bright)
+    Ansi(u8),
+    // one of the 256 colors of the extended palette
+    Fixed(u8),
+    // a 24-bit "truecolor"
+    Rgb(u8, u8, u8),
+    // raw SGR parameters, e.g. os-release's ANSI_COLOR="0;34"
+    Sgr(String),
+}
+
+const NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+impl Color {
+    // parse a color number (0-255), name (`red`, `bright-red`)
+    // or hex code (`#ff8800`).
+    pub fn parse(color: &str) -> Result<Color> {
+        let color = color.trim().to_lowercase();
+
+        if let Ok(n) = color.parse::<u8>() {
+            if n < 16 {
+                return Ok(Color::Ansi(n));
+            } else {
+                return Ok(Color::Fixed(n));
+            }
+        }
+
+        if let Some(hex) = color.strip_prefix('#') {
+            let rgb = u32::from_str_radix(hex, 16).ok();
+            if let (6, Some(rgb)) = (hex.len(), rgb) {
+                return Ok(Color::Rgb(
+                    (rgb >> 16) as u8,
+                    (rgb >> 8) as u8,
+                    rgb as u8,
+                ));
+            }
+        }
+
+        let (name, offset) = if let Some(name) = color.strip_prefix("bright-") {
+            (name, 8)
+        } else {
+            (color.as_str(), 0)
+        };
+
+        let n = NAMES
+            .iter()
+            .position(|c| c == &name)
+            .context(InvalidColor {
+                color: color.clone(),
+            })?;
+
+        Ok(Color::Ansi(n as u8 + offset))
+    }
+
+    // the escape sequence that switches the foreground to this color.
+    pub fn escape(&self) -> String {
+        match self {
+            Color::Ansi(n) if *n < 8 => format!("{}[{}m", E, 30 + n),
+            Color::Ansi(n) => format!("{}[{}m", E, 90 + n - 8),
+            Color::Fixed(n) => format!("{}[38;5;{}m", E, n),
+            Color::Rgb(r, g, b) => format!("{}[38;2;{};{};{}m", E, r, g, b),
+            Color::Sgr(params) => format!("{}[{}m", E, params),
+        }
+    }
+
+    // the escape sequence that switches the background to this color.
+    // raw SGR parameters are used as-is.
+    pub fn background(&self) -> String {
+        match self {
+            Color::Ansi(n) if *n < 8 => format!("{}[{}m", E, 40 + n),
+            Color::Ansi(n) => format!("{}[{}m", E, 100 + n - 8),
+            Color::Fixed(n) => format!("{}[48;5;{}m", E, n),
+            Color::Rgb(r, g, b) => format!("{}[48;2;{};{};{}m", E, r, g, b),
+            Color::Sgr(params) => format!("{}[{}m", E, params),
+        }
+    }
+}
+
+// parse a comma-separated list of colors, e.g. `--logo-colors 4,bright-white`.
+pub fn parse_colors(list: &str) -> Result<Vec<Color>> {
+    list.split(',').map(Color::parse).collect()
+}
+
+// the colors of the different parts of the output.
+#[derive(Clone, Default)]
+pub struct Theme {
+    pub key: Option<Color>,
+    pub value: Option<Color>,
+    pub separator: Option<Color>,
+    pub border: Option<Color>,
+    pub corner: Option<Color>,
+    // colors for the keys and values of single fields, by their (uppercase) key
+    pub fields: HashMap<String, Color>,
+}
+
+// key, value, separator, border, corner
+const PRESETS: &[(&str, [&str; 5])] = &[
+    ("ocean", ["12", "15", "6", "4", "14"]),
+    ("forest", ["2", "7", "10", "22", "10"]),
+    ("sunset", ["#ff8c42", "#ffd6a5", "#ff6f59", "#c1440e", "#ffb400"]),
+    ("dracula", ["#bd93f9", "#f8f8f2", "#ff79c6", "#6272a4", "#50fa7b"]),
+    ("gruvbox", ["#fe8019", "#ebdbb2", "#fabd2f", "#928374", "#b8bb26"]),
+    ("nord", ["#88c0d0", "#eceff4", "#81a1c1", "#4c566a", "#8fbcbb"]),
+];
+
+impl Theme {
+    // get one of the named presets, `default` being no colors at all.
+    pub fn preset(name: &str) -> Result<Theme> {
+        if name == "default" {
+            return Ok(Theme::default());
+        }
+
+        let (_, colors) = PRESETS
+            .iter()
+            .find(|(n, _)| n == &name)
+            .context(UnknownTheme {
+                name: name.to_string(),
+            })?;
+
+        Ok(Theme {
+            key: Some(Color::parse(colors[0])?),
+            value: Some(Color::parse(colors[1])?),
+            separator: Some(Color::parse(colors[2])?),
+            border: Some(Color::parse(colors[3])?),
+            corner: Some(Color::parse(colors[4])?),
+            fields: HashMap::new(),
+        })
+    }
+
+    // use the same color for every part.
+    pub fn set_all(&mut self, color: Color) {
+        self.key = Some(color.clone());
+        self.value = Some(color.clone());
+        self.separator = Some(color.clone());
+        self.border = Some(color.clone());
+        self.corner = Some(color);
+    }
+
+    // color a single field, e.g. `--field-color MEMORY=red`.
+    pub fn set_field(&mut self, spec: &str) -> Result<()> {
+        let mut parts = spec.splitn(2, '=');
+        let key = parts.next().unwrap_or_default().trim();
+        let color = parts.next().filter(|_| !key.is_empty()).context(InvalidFieldColor {
+            spec: spec.to_string(),
+        })?;
+        self.fields.insert(key.to_uppercase(), Color::parse(color.trim())?);
+        Ok(())
+    }
+
+    // the key and value colors of a field. `MUSIC` also matches `MUSIC (MPD)`.
+    pub fn field(&self, key: &str) -> (Option<Color>, Option<Color>) {
+        let key = key.to_uppercase();
+        let short = key.split(" (").next().unwrap_or_default();
+        match self.fields.get(&key).or_else(|| self.fields.get(short)) {
+            Some(c) => (Some(c.clone()), Some(c.clone())),
+            None => (self.key.clone(), self.value.clone()),
+        }
+    }
+}
+
+// the names of all the theme presets.
+pub fn get_theme_names() -> Vec<&'static str> {
+    let mut names = vec!["default"];
+    names.extend(PRESETS.iter().map(|(n, _)| *n));
+    names
+}
@@ -73,7 +73,7 @@ impl CPUInfo {
                 .trim()
                 .to_string();
 
-            if speed == "" {
+            if speed.is_empty() {
                 let cpu_clockrate = Command::new("sysctl")
                     .arg("-n")
                     .arg("hw.clockrate")
@@ -6,7 +6,9 @@ pub struct DistroInfo {
     name: String,
     pretty_name: String,
     id: String,
+    id_like: String,
     distrib_id: String,
+    ansi_color: String,
 }
 
 impl DistroInfo {
@@ -15,7 +17,9 @@ impl DistroInfo {
             name: String::new(),
             pretty_name: String::new(),
             id: String::new(),
+            id_like: String::new(),
             distrib_id: String::new(),
+            ansi_color: String::new(),
         }
     }
 
@@ -30,28 +34,22 @@ impl DistroInfo {
 
         // check for CRUX
         let isthiscrux = Command::new("crux").output();
-        match isthiscrux {
-            Ok(__) => {
-                // TODO: parse output of `crux` command
-                // into self.name and self.pretty_name
-                self.name = "crux".to_string();
-                self.pretty_name = "CRUX Linux".to_string();
-
-                return Ok(());
-            }
-            Err(_) => (),
+        if isthiscrux.is_ok() {
+            // TODO: parse output of `crux` command
+            // into self.name and self.pretty_name
+            self.name = "crux".to_string();
+            self.pretty_name = "CRUX Linux".to_string();
+
+            return Ok(());
         }
 
         // check for GNU Guix
         let isthisguix = Command::new("guix").output();
-        match isthisguix {
-            Ok(_) => {
-                self.name = "guix".to_string();
-                self.pretty_name = "Guix System".to_string();
+        if isthisguix.is_ok() {
+            self.name = "guix".to_string();
+            self.pretty_name = "Guix System".to_string();
 
-                return Ok(());
-            }
-            Err(_) => (),
+            return Ok(());
         }
 
         // check for /etc/os-release file
@@ -70,6 +68,8 @@ impl DistroInfo {
                 match key {
                     "NAME" => self.name = val.to_string(),
                     "ID" => self.id = val.to_string(),
+                    "ID_LIKE" => self.id_like = val.to_string(),
+                    "ANSI_COLOR" => self.ansi_color = val.to_string(),
                     "DISTRIB_ID" => self.distrib_id = val.to_string(),
                     "PRETTY_NAME" => self.pretty_name = val.to_string(),
                     &_ => (),
@@ -95,6 +95,8 @@ impl DistroInfo {
                 match key {
                     "NAME" => self.name = val.to_string(),
                     "ID" => self.id = val.to_string(),
+                    "ID_LIKE" => self.id_like = val.to_string(),
+                    "ANSI_COLOR" => self.ansi_color = val.to_string(),
                     "DISTRIB_ID" => self.distrib_id = val.to_string(),
                     "PRETTY_NAME" => self.pretty_name = val.to_string(),
                     &_ => (),
@@ -123,8 +125,41 @@ impl DistroInfo {
         }
     }
 
+    // all the IDs this system goes by, most specific first:
+    // `ID`, then each of `ID_LIKE`, then the plain name.
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids = Vec::new();
+        if !self.id.is_empty() {
+            ids.push(self.id.to_lowercase());
+        }
+
+        self.id_like
+            .split_whitespace()
+            .for_each(|i| ids.push(i.to_lowercase()));
+
+        if !self.distrib_id.is_empty() {
+            ids.push(self.distrib_id.to_lowercase());
+        }
+
+        let name = self.name.trim().to_lowercase();
+        if !name.is_empty() {
+            ids.push(name);
+        }
+
+        ids
+    }
+
+    // the SGR parameters from os-release's ANSI_COLOR, if any.
+    pub fn ansi_color(&self) -> Option<String> {
+        if self.ansi_color.is_empty() {
+            None
+        } else {
+            Some(self.ansi_color.clone())
+        }
+    }
+
     pub fn format(&self) -> String {
-        if self.pretty_name != "" {
+        if !self.pretty_name.is_empty() {
             self.pretty_name.clone()
         } else {
             self.name.clone()
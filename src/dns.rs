@@ -0,0 +1,131 @@
+// the DNS servers and search domains, from /etc/resolv.conf.
+use crate::*;
+use std::fs;
+
+// the addresses systemd-resolved's local stub resolver listens on
+const RESOLVED_STUBS: [&str; 2] = ["127.0.0.53", "127.0.0.54"];
+
+pub struct DnsInfo {
+    pub servers: Vec<String>,
+    pub search: Vec<String>,
+    // whether the servers are the ones systemd-resolved forwards to
+    pub resolved: bool,
+}
+
+impl DnsInfo {
+    pub fn new() -> DnsInfo {
+        DnsInfo {
+            servers: Vec::new(),
+            search: Vec::new(),
+            resolved: false,
+        }
+    }
+
+    pub fn get(&mut self) -> Result<()> {
+        let conf = fs::read_to_string("/etc/resolv.conf").context(ReadResolvConf)?;
+        let (servers, search) = parse(&conf);
+        self.servers = servers;
+        self.search = search;
+
+        // behind systemd-resolved's stub, the real servers are in its own file
+        let stub = !self.servers.is_empty() && self.servers.iter().all(|s| RESOLVED_STUBS.contains(&s.as_str()));
+        if stub {
+            if let Ok(conf) = fs::read_to_string("/run/systemd/resolve/resolv.conf") {
+                let (servers, search) = parse(&conf);
+                if !servers.is_empty() {
+                    self.servers = servers;
+                    self.resolved = true;
+                }
+                if self.search.is_empty() {
+                    self.search = search;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // format it, e.g. `1.1.1.1, 9.9.9.9 (systemd-resolved)`,
+    // with the search domains on a line of their own
+    pub fn format(&self) -> String {
+        let mut dns = if self.servers.is_empty() {
+            "none".to_string()
+        } else {
+            self.servers.join(", ")
+        };
+        if self.resolved {
+            dns.push_str(" (systemd-resolved)");
+        }
+        if !self.search.is_empty() {
+            dns = format!("{}\nsearch {}", dns, self.search.join(", "));
+        }
+
+        dns
+    }
+}
+
+// the name servers and search domains of a resolv.conf. like the
+// resolver, the last `search` or `domain` line is the one that counts.
+fn parse(conf: &str) -> (Vec<String>, Vec<String>) {
+    let mut servers = Vec::new();
+    let mut search = Vec::new();
+
+    for line in conf.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("nameserver") => servers.extend(words.next().map(str::to_string)),
+            Some("search") | Some("domain") => {
+                search = words
+                    .take_while(|w| !w.starts_with('#') && !w.starts_with(';'))
+                    .map(str::to_string)
+                    .collect()
+            }
+            _ => (),
+        }
+    }
+
+    (servers, search)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing() {
+        let conf = "# Generated by NetworkManager
+search home.arpa example.com # the office
+nameserver 192.0.2.53
+; nameserver 192.0.2.54
+#nameserver 192.0.2.55
+nameserver\tfd00::53
+options edns0 trust-ad
+";
+        let (servers, search) = parse(conf);
+        assert_eq!(servers, vec!["192.0.2.53", "fd00::53"]);
+        assert_eq!(search, vec!["home.arpa", "example.com"]);
+    }
+
+    #[test]
+    fn last_search_wins() {
+        let (servers, search) = parse("domain example.com\nsearch home.arpa\n");
+        assert!(servers.is_empty());
+        assert_eq!(search, vec!["home.arpa"]);
+
+        let (_, search) = parse("search home.arpa\ndomain example.com ;old\n");
+        assert_eq!(search, vec!["example.com"]);
+
+        assert_eq!(parse(""), (Vec::new(), Vec::new()));
+    }
+
+    #[test]
+    fn formatting() {
+        let mut dns = DnsInfo::new();
+        assert_eq!(dns.format(), "none");
+
+        dns.servers = vec!["1.1.1.1".to_string(), "9.9.9.9".to_string()];
+        dns.resolved = true;
+        dns.search = vec!["home.arpa".to_string()];
+        assert_eq!(dns.format(), "1.1.1.1, 9.9.9.9 (systemd-resolved)\nsearch home.arpa");
+    }
+}
@@ -26,18 +26,15 @@ impl EnvInfo {
         match item {
             EnvItem::User => {
                 let command = Command::new("id").arg("-un").output();
-                match command {
-                    Ok(o) => {
-                        let user = String::from_utf8(o.stdout)
-                            .unwrap()
-                            .replace("\n", "");
+                if let Ok(o) = command {
+                    let user = String::from_utf8(o.stdout)
+                        .unwrap()
+                        .replace("\n", "");
 
-                        if user != "" {
-                            self.user = user;
-                            return Ok(());
-                        }
+                    if !user.is_empty() {
+                        self.user = user;
+                        return Ok(());
                     }
-                    Err(_) => (),
                 }
 
                 // fallback to reading the USER variable
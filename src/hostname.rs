@@ -1,6 +1,10 @@
 use crate::*;
+use libc::{addrinfo, freeaddrinfo, getaddrinfo, AI_CANONNAME};
+use std::ffi::{CStr, CString};
 use std::fs;
+use std::mem;
 use std::process::Command;
+use std::ptr;
 
 pub struct Hostname {
     name: String,
@@ -33,6 +37,29 @@ impl Hostname {
         Ok(())
     }
 
+    // the fully qualified domain name, as the resolver knows it
+    // (from /etc/hosts, DNS, ...), if it's more than the hostname.
+    pub fn fqdn(&self) -> Option<String> {
+        let name = CString::new(self.name.as_str()).ok()?;
+        let mut hints: addrinfo = unsafe { mem::zeroed() };
+        hints.ai_flags = AI_CANONNAME;
+
+        let mut info: *mut addrinfo = ptr::null_mut();
+        if unsafe { getaddrinfo(name.as_ptr(), ptr::null(), &hints, &mut info) } != 0 || info.is_null() {
+            return None;
+        }
+
+        let canonical = unsafe { (*info).ai_canonname };
+        let fqdn = if canonical.is_null() {
+            None
+        } else {
+            Some(unsafe { CStr::from_ptr(canonical) }.to_string_lossy().into_owned())
+        };
+        unsafe { freeaddrinfo(info) };
+
+        fqdn.filter(|f| f.contains('.') && f != &self.name)
+    }
+
     // format it
     pub fn format(&self) -> String {
         self.name.clone()
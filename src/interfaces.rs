@@ -0,0 +1,378 @@
+// the local network interfaces that are up, with their addresses,
+// and which of them the default route goes through.
+use crate::*;
+use libc::{c_int, freeifaddrs, getifaddrs, ifaddrs, sockaddr, sockaddr_in, sockaddr_in6};
+use libc::{AF_INET, AF_INET6, IFF_LOOPBACK, IFF_UP};
+use std::ffi::CStr;
+use std::fs;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use std::ptr;
+
+// route flags, see route(8)
+const RTF_UP: u32 = 0x0001;
+const RTF_REJECT: u32 = 0x0200;
+
+pub struct Interface {
+    pub name: String,
+    // with their prefix length, e.g. `192.168.1.5/24`
+    pub addresses: Vec<(IpAddr, u32)>,
+    pub mac: Option<String>,
+    pub loopback: bool,
+    // whether a default route goes through it
+    pub default: bool,
+}
+
+pub struct InterfaceInfo {
+    pub interfaces: Vec<Interface>,
+}
+
+impl InterfaceInfo {
+    pub fn new() -> InterfaceInfo {
+        InterfaceInfo {
+            interfaces: Vec::new(),
+        }
+    }
+
+    // list the interfaces that are up, the default route's first.
+    // `hide_virtual` leaves out loopback, bridges and the virtual
+    // interfaces plugged into them (e.g. Docker's and libvirt's).
+    pub fn get(&mut self, hide_virtual: bool) -> Result<()> {
+        let mut list: *mut ifaddrs = ptr::null_mut();
+        if unsafe { getifaddrs(&mut list) } != 0 {
+            Err(io::Error::last_os_error()).context(Interfaces)?;
+        }
+
+        let mut entry = list;
+        while !entry.is_null() {
+            let ifa = unsafe { &*entry };
+            entry = ifa.ifa_next;
+
+            if ifa.ifa_flags & IFF_UP as u32 == 0 || ifa.ifa_addr.is_null() {
+                continue;
+            }
+
+            let name = unsafe { CStr::from_ptr(ifa.ifa_name) }.to_string_lossy().into_owned();
+            let index = match self.interfaces.iter().position(|i| i.name == name) {
+                Some(i) => i,
+                None => {
+                    self.interfaces.push(Interface {
+                        name,
+                        addresses: Vec::new(),
+                        mac: None,
+                        loopback: ifa.ifa_flags & IFF_LOOPBACK as u32 != 0,
+                        default: false,
+                    });
+                    self.interfaces.len() - 1
+                }
+            };
+            let interface = &mut self.interfaces[index];
+
+            let addr = unsafe { &*ifa.ifa_addr };
+            let netmask = unsafe { ifa.ifa_netmask.as_ref() };
+            match addr.sa_family as c_int {
+                AF_INET => {
+                    let ip = unsafe { &*(addr as *const sockaddr as *const sockaddr_in) };
+                    let prefix = match netmask {
+                        Some(mask) => unsafe { &*(mask as *const sockaddr as *const sockaddr_in) }
+                            .sin_addr
+                            .s_addr
+                            .count_ones(),
+                        None => 32,
+                    };
+                    let ip = Ipv4Addr::from(u32::from_be(ip.sin_addr.s_addr));
+                    interface.addresses.push((IpAddr::V4(ip), prefix));
+                }
+                AF_INET6 => {
+                    let ip = unsafe { &*(addr as *const sockaddr as *const sockaddr_in6) };
+                    let prefix = match netmask {
+                        Some(mask) => unsafe { &*(mask as *const sockaddr as *const sockaddr_in6) }
+                            .sin6_addr
+                            .s6_addr
+                            .iter()
+                            .map(|b| b.count_ones())
+                            .sum(),
+                        None => 128,
+                    };
+                    // link-local addresses are derived from the MAC, and hardly tell anything
+                    let ip = Ipv6Addr::from(ip.sin6_addr.s6_addr);
+                    if ip.segments()[0] & 0xffc0 != 0xfe80 {
+                        interface.addresses.push((IpAddr::V6(ip), prefix));
+                    }
+                }
+                _ => {
+                    if let Some(mac) = mac(addr) {
+                        interface.mac = Some(mac);
+                    }
+                }
+            }
+        }
+        unsafe { freeifaddrs(list) };
+
+        if hide_virtual {
+            self.interfaces.retain(|i| !i.loopback && !is_bridge(&i.name));
+        }
+
+        let defaults = default_interfaces();
+        for interface in self.interfaces.iter_mut() {
+            interface.default = defaults.contains(&interface.name);
+        }
+        self.interfaces.sort_by_key(|i| !i.default);
+
+        Ok(())
+    }
+
+    // format it, one interface per line:
+    // `eth0: 192.168.1.5/24, fd00::5/64 - aa:bb:cc:dd:ee:ff (default)`
+    pub fn format(&self) -> String {
+        if self.interfaces.is_empty() {
+            return "none".to_string();
+        }
+
+        self.interfaces
+            .iter()
+            .map(|i| {
+                let mut details = Vec::new();
+                if !i.addresses.is_empty() {
+                    let addresses = i
+                        .addresses
+                        .iter()
+                        .map(|(ip, prefix)| format!("{}/{}", ip, prefix))
+                        .collect::<Vec<String>>();
+                    details.push(addresses.join(", "));
+                }
+                if let Some(mac) = &i.mac {
+                    details.push(mac.clone());
+                }
+
+                let mut line = i.name.clone();
+                if !details.is_empty() {
+                    line = format!("{}: {}", line, details.join(" - "));
+                }
+                if i.default {
+                    line.push_str(" (default)");
+                }
+                line
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+pub struct GatewayInfo {
+    pub routes: Vec<Route>,
+}
+
+impl GatewayInfo {
+    pub fn new() -> GatewayInfo {
+        GatewayInfo { routes: Vec::new() }
+    }
+
+    pub fn get(&mut self) -> Result<()> {
+        self.routes = default_routes().context(ReadRoutes)?;
+        Ok(())
+    }
+
+    // format it, e.g. `192.168.1.1 (eth0), fe80::1 (eth0)`, with just the
+    // interface for routes without a gateway
+    pub fn format(&self) -> String {
+        let mut gateways: Vec<String> = Vec::new();
+        for route in &self.routes {
+            let gateway = match route.gateway {
+                Some(ip) => format!("{} ({})", ip, route.interface),
+                None => route.interface.clone(),
+            };
+            if !gateways.contains(&gateway) {
+                gateways.push(gateway);
+            }
+        }
+
+        if gateways.is_empty() {
+            "none".to_string()
+        } else {
+            gateways.join(", ")
+        }
+    }
+}
+
+// a default route: the interface it goes through, and the gateway
+// it goes to, unless it is a point-to-point link (e.g. a VPN's).
+pub struct Route {
+    pub interface: String,
+    pub gateway: Option<IpAddr>,
+}
+
+// the default IPv4 and IPv6 routes. without IPv6, there's no
+// /proc/net/ipv6_route, which just means there are no IPv6 routes.
+pub fn default_routes() -> io::Result<Vec<Route>> {
+    let mut routes = parse_routes(&fs::read_to_string("/proc/net/route")?);
+    if let Ok(table) = fs::read_to_string("/proc/net/ipv6_route") {
+        routes.extend(parse_ipv6_routes(&table));
+    }
+    Ok(routes)
+}
+
+// whether a route is up, and not a blackhole, from its flags in hex.
+fn route_up(flags: &str) -> bool {
+    u32::from_str_radix(flags, 16)
+        .map(|f| f & RTF_UP != 0 && f & RTF_REJECT == 0)
+        .unwrap_or(false)
+}
+
+// the default routes in /proc/net/route, which has a header line, then:
+// interface, destination, gateway, flags, refcnt, use, metric, mask, ...
+// with the addresses in network byte order, printed as a host's integer
+fn parse_routes(table: &str) -> Vec<Route> {
+    let mut routes = Vec::new();
+    for line in table.lines().skip(1) {
+        let route = line.split_whitespace().collect::<Vec<&str>>();
+        if route.len() > 7 && route[1] == "00000000" && route[7] == "00000000" && route_up(route[3]) {
+            let gateway = u32::from_str_radix(route[2], 16)
+                .ok()
+                .filter(|g| *g != 0)
+                .map(|g| IpAddr::V4(Ipv4Addr::from(g.to_ne_bytes())));
+            routes.push(Route {
+                interface: route[0].to_string(),
+                gateway,
+            });
+        }
+    }
+    routes
+}
+
+// the default routes in /proc/net/ipv6_route: destination, its prefix length,
+// source, its prefix length, next hop, metric, refcnt, use, flags, interface
+fn parse_ipv6_routes(table: &str) -> Vec<Route> {
+    let mut routes = Vec::new();
+    for line in table.lines() {
+        let route = line.split_whitespace().collect::<Vec<&str>>();
+        let default = route.len() > 9 && route[1] == "00" && route[0].chars().all(|c| c == '0');
+        if default && route_up(route[8]) {
+            let gateway = u128::from_str_radix(route[4], 16)
+                .ok()
+                .filter(|g| *g != 0)
+                .map(|g| IpAddr::V6(Ipv6Addr::from(g)));
+            routes.push(Route {
+                interface: route[9].to_string(),
+                gateway,
+            });
+        }
+    }
+    routes
+}
+
+// the interfaces the default routes go through.
+pub fn default_interfaces() -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for route in default_routes().unwrap_or_default() {
+        if !names.contains(&route.interface) {
+            names.push(route.interface);
+        }
+    }
+    names
+}
+
+// whether the interface is a bridge, or a virtual interface plugged into one.
+fn is_bridge(name: &str) -> bool {
+    let class = Path::new("/sys/class/net").join(name);
+    class.join("bridge").exists()
+        || (class.join("brport").exists() && Path::new("/sys/devices/virtual/net").join(name).exists())
+}
+
+// the hardware address of a link-layer entry, e.g. `aa:bb:cc:dd:ee:ff`.
+#[cfg(target_os = "linux")]
+fn mac(addr: &sockaddr) -> Option<String> {
+    if addr.sa_family as c_int != libc::AF_PACKET {
+        return None;
+    }
+
+    let link = unsafe { &*(addr as *const sockaddr as *const libc::sockaddr_ll) };
+    let bytes = &link.sll_addr[..(link.sll_halen as usize).min(link.sll_addr.len())];
+    if bytes.iter().all(|b| *b == 0) {
+        return None;
+    }
+
+    Some(
+        bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<String>>()
+            .join(":"),
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+fn mac(_addr: &sockaddr) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the routes as `interface gateway`, or just the interface
+    fn list(routes: &[Route]) -> Vec<String> {
+        routes
+            .iter()
+            .map(|r| match r.gateway {
+                Some(ip) => format!("{} {}", r.interface, ip),
+                None => r.interface.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ipv4_routes() {
+        // addresses are printed as the host reads them
+        let hex = |ip: [u8; 4]| format!("{:08X}", u32::from_ne_bytes(ip));
+        let (gateway, network) = (hex([192, 0, 2, 1]), hex([192, 0, 2, 0]));
+        let table = [
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT".to_string(),
+            format!("eth0\t00000000\t{}\t0003\t0\t0\t100\t00000000\t0\t0\t0", gateway),
+            format!("eth0\t{}\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0", network),
+            // point-to-point, down, unreachable, and half of the internet (e.g. OpenVPN's)
+            "tun0\t00000000\t00000000\t0001\t0\t0\t50\t00000000\t0\t0\t0".to_string(),
+            "eth1\t00000000\t00000000\t0002\t0\t0\t0\t00000000\t0\t0\t0".to_string(),
+            "*\t00000000\t00000000\t0201\t0\t0\t0\t00000000\t0\t0\t0".to_string(),
+            "tun1\t00000000\t00000000\t0001\t0\t0\t0\t00000080\t0\t0\t0".to_string(),
+        ]
+        .join("\n");
+
+        assert_eq!(list(&parse_routes(&table)), vec!["eth0 192.0.2.1", "tun0"]);
+        assert!(parse_routes("Iface\tDestination\tGateway \tFlags\n").is_empty());
+    }
+
+    #[test]
+    fn ipv6_routes() {
+        let any = "00000000000000000000000000000000";
+        let table = [
+            format!("{0} 00 {0} 00 fd000000000000000000000000000001 00000400 00000001 00000000 00000003     eth0", any),
+            format!("fd000000000000000000000000000000 40 {0} 00 {0} 00000100 00000001 00000000 00000001     eth0", any),
+            format!("{0} 00 {0} 00 {0} 00000400 00000001 00000000 00000001      wg0", any),
+            format!("{0} 00 {0} 00 {0} ffffffff 00000001 00000000 00200200       lo", any),
+        ]
+        .join("\n");
+
+        assert_eq!(list(&parse_ipv6_routes(&table)), vec!["eth0 fd00::1", "wg0"]);
+    }
+
+    #[test]
+    fn gateways() {
+        let route = |interface: &str, gateway: Option<&str>| Route {
+            interface: interface.to_string(),
+            gateway: gateway.map(|g| g.parse().unwrap()),
+        };
+
+        let mut info = GatewayInfo::new();
+        assert_eq!(info.format(), "none");
+
+        info.routes = vec![
+            route("eth0", Some("192.0.2.1")),
+            route("eth0", Some("192.0.2.1")),
+            route("eth0", Some("fd00::1")),
+            route("wg0", None),
+        ];
+        assert_eq!(info.format(), "192.0.2.1 (eth0), fd00::1 (eth0), wg0");
+    }
+}
@@ -0,0 +1,603 @@
+// built-in ASCII logos, selected by name or by the
+// os-release ID/ID_LIKE of the running system.
+//
+// most of the art below comes from `neofetch`, which
+// is where the idea (and the expectation) came from.
+
+use crate::*;
+use std::fs;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum LogoSize {
+    Small,
+    Large,
+}
+
+impl LogoSize {
+    pub fn from(size: &str) -> LogoSize {
+        match size {
+            "small" => LogoSize::Small,
+            _ => LogoSize::Large,
+        }
+    }
+}
+
+// a logo ready to be printed, with the colors
+// used for its `${c1}`..`${c6}` placeholders.
+pub struct LogoArt {
+    pub art: String,
+    pub colors: Vec<Color>,
+}
+
+struct Logo {
+    // the first name is the canonical one, the rest
+    // are os-release IDs that should map to the same art.
+    names: &'static [&'static str],
+    // ANSI color numbers for `${c1}`, `${c2}`, etc.
+    colors: &'static [u8],
+    small: &'static str,
+    large: &'static str,
+}
+
+const LOGOS: &[Logo] = &[
+    Logo {
+        names: &["arch", "archlinux", "archarm"],
+        colors: &[6, 6],
+        small: r#"      /\
+     /  \
+    /\   \
+   /      \
+  /   ,,   \
+ /   |  |  -\
+/_-''    ''-_\"#,
+        large: r#"                   -`
+                  .o+`
+                 `ooo/
+                `+oooo:
+               `+oooooo:
+               -+oooooo+:
+             `/:-:++oooo+:
+            `/++++/+++++++:
+           `/++++++++++++++:
+          `/+++ooooooooooooo/`
+         ./ooosssso++osssssso+`
+        .oossssso-````/ossssss+`
+       -osssssso.      :ssssssso.
+      :osssssss/        osssso+++.
+     /ossssssss/        +ssssooo/-
+   `/ossssso+/:-        -:/+osssso+-
+  `+sso+:-`                 `.-/+oso:
+ `++:.                           `-/+/
+ .`                                 `/"#,
+    },
+    Logo {
+        names: &["debian"],
+        colors: &[1, 7],
+        small: r#"  _____
+ /  __ \
+|  /    |
+|  \___-
+-_
+  --_"#,
+        large: r#"       _,met$$$$$gg.
+    ,g$$$$$$$$$$$$$$$P.
+  ,g$$P"     """Y$$.".
+ ,$$P'              `$$$.
+',$$P       ,ggs.     `$$b:
+`d$$'     ,$P"'   .    $$$
+ $$P      d$'     ,    $$P
+ $$:      $$.   -    ,d$$'
+ $$;      Y$b._   _,d$P'
+ Y$$.    `.`"Y$$$$P"'
+ `$$b      "-.__
+  `Y$$
+   `Y$$.
+     `$$b.
+       `Y$$b.
+          `"Y$b._
+              `""""#,
+    },
+    Logo {
+        names: &["ubuntu"],
+        colors: &[1, 7, 3],
+        small: r#"         _
+     ---(_)
+ _/  ---  \
+(_) |   |
+  \  --- _/
+     ---(_)"#,
+        large: r#"            .-/+oossssoo+/-.
+        `:+ssssssssssssssssss+:`
+      -+ssssssssssssssssssyyssss+-
+    .ossssssssssssssssssdMMMNysssso.
+   /ssssssssssshdmmNNmmyNMMMMhssssss/
+  +ssssssssshmydMMMMMMMNddddyssssssss+
+ /sssssssshNMMMyhhyyyyhmNMMMNhssssssss/
+.ssssssssdMMMNhsssssssssshNMMMdssssssss.
++sssshhhyNMMNyssssssssssssyNMMMysssssss+
+ossyNMMMNyMMhsssssssssssssshmmmhssssssso
+ossyNMMMNyMMhsssssssssssssshmmmhssssssso
++sssshhhyNMMNyssssssssssssyNMMMysssssss+
+.ssssssssdMMMNhsssssssssshNMMMdssssssss.
+ /sssssssshNMMMyhhyyyyhdNMMMNhssssssss/
+  +sssssssssdmydMMMMMMMMddddyssssssss+
+   /ssssssssssshdmNNNNmyNMMMMhssssss/
+    .ossssssssssssssssssdMMMNysssso.
+      -+sssssssssssssssssyyyssss+-
+        `:+ssssssssssssssssss+:`
+            .-/+oossssoo+/-."#,
+    },
+    Logo {
+        names: &["fedora"],
+        colors: &[4, 7],
+        small: r#"      _____
+     /   __)\
+     |  /  \ \
+  ___|  |__/ /
+ / (_    _)_/
+/ /  |  |
+\ \__/  |
+ \(_____/"#,
+        large: r#"          /:-------------:\
+       :-------------------::
+     :-----------/shhOHbmp---:\
+   /-----------omMMMNNNMMD  ---:
+  :-----------sMMMMNMNMP.    ---:
+ :-----------:MMMdP-------    ---\
+,------------:MMMd--------    ---:
+:------------:MMMd-------    .---:
+:----    oNMMMMMMMMMNho     .----:
+:--     .+shhhMMMmhhy++   .------/
+:-    -------:MMMd--------------:
+:-   --------/MMMd-------------;
+:-    ------/hMMMy------------:
+:-- :dMNdhhdNMMNo------------;
+:---:sdNMMMMNds:------------:
+:------:://:-------------::
+:---------------------://"#,
+    },
+    Logo {
+        names: &["void"],
+        colors: &[2, 8],
+        small: r#"    _______
+ _ \______ -
+| \  ___  \ |
+| | /   \ | |
+| | \___/ | |
+| \______ \_|
+ -_______\"#,
+        large: r#"                __.;=====;.__
+            _.=+==++=++=+=+===;.
+             -=+++=+===+=+=+++++=_
+        .     -=:``     `--==+=++==.
+       _vi,    `            --+=++++:
+      .uvnvi.       _._       -==+==+.
+     .vvnvnI`    .;==|==;.     :|=||=|.
++QmQQmpvvnv; _yYsyQQWUUQQQm #QmQ#:QQQWUV$QQm.
+ -QQWQWpvvowZ?.wQQQE==<QWWQ/QWQW.QQWW(: jQWQE
+  -$QQQQmmU'  jQQQ@+=<QWQQ)mQQQ.mQQQC+;jWQQ@'
+   -$WQ8YnI:   QWQQwgQQWV`mWQQ.jQWQQgyyWW@!
+     -1vvnvv.     `~+++`        ++|+++
+      +vnvnnv,                 `-|===
+       +vnvnvns.           .      :=-
+        -Invnvvnsi..___..=sv=.     `
+          +Invnvnvnnnnnnnnvvnn;.
+            ~|Invnvnvvnvvvnnv}+`
+               -~|{*l}*|~"#,
+    },
+    Logo {
+        names: &["gentoo"],
+        colors: &[5, 7],
+        small: r#" _-----_
+(       \
+\    ${c2}0${c1}   \
+ \        )
+ /      _/
+(     _-
+\____-"#,
+        large: r#"         -/oyddmdhs+:.
+     -odNMMMMMMMMNNmhy+-`
+   -yNMMMMMMMMMMMNNNmmdhy+-
+ `omMMMMMMMMMMMMNmdmmmmddhhy/`
+ omMMMMMMMMMMMNhhyyyohmdddhhhdo`
+.ydMMMMMMMMMMdhs++so/smdddhhhhdm+`
+ oyhdmNMMMMMMMNdyooydmddddhhhhyhNd.
+  :oyhhdNNMMMMMMMNNNmmdddhhhhhyymMh
+    .:+sydNMMMMMNNNmmmdddhhhhhhmMmy
+       /mMMMMMMNNNmmmdddhhhhhmMNhs:
+    `oNMMMMMMMNNNmmmddddhhdmMNhs+`
+  `sNMMMMMMMMNNNmmmdddddmNMmhs/.
+ /NMMMMMMMMNNNNmmmdddmNMNdso:`
++MMMMMMMNNNNNmmmmdmNMNdso/-
+yMMNNNNNNNmmmmmNNMmhs+/-`
+/hMMNNNNNNNNMNdhs++/-`
+`/ohdmmddhys+++/:.`
+  `-//////:--."#,
+    },
+    Logo {
+        names: &["alpine"],
+        colors: &[4, 7],
+        small: r#"   /\ /\
+  // \  \
+ //   \  \
+///    \  \
+//      \  \
+         \"#,
+        large: r#"       .hddddddddddddddddddddddh.
+      :dddddddddddddddddddddddddd:
+     /dddddddddddddddddddddddddddd/
+    +dddddddddddddddddddddddddddddd+
+  `sdddddddddddddddddddddddddddddddds`
+ `ydddddddddddd++hdddddddddddddddddddy`
+.hddddddddddd+`  `+ddddh:-sdddddddddddh.
+hdddddddddd+`      `+y:    .sddddddddddh
+ddddddddh+`   `//`   `.`     -sddddddddd
+ddddddh+`   `/hddh/`   `:s-    -sddddddd
+ddddh+`   `/+/dddddh/`   `+s-    -sddddd
+ddd+`   `/o` :dddddddh/`   `oy-    .yddd
+hdddyo+ohddyosdddddddddho+oydddy++ohdddh
+.hddddddddddddddddddddddddddddddddddddh.
+ `yddddddddddddddddddddddddddddddddddy`
+  `sdddddddddddddddddddddddddddddddds`
+    +dddddddddddddddddddddddddddddd+
+     /dddddddddddddddddddddddddddd/
+      :dddddddddddddddddddddddddd:
+       .hddddddddddddddddddddddh."#,
+    },
+    Logo {
+        names: &["nixos", "nix"],
+        colors: &[4, 6],
+        small: r#"  \\  \\ //
+ ==\\__\\/ //
+   //   \\//
+==//     //==
+ //\\___//
+// /\\  \\==
+  // \\  \\"#,
+        large: r#"          ::::.    ':::::     ::::'
+          ':::::    ':::::.  ::::'
+            :::::     '::::.:::::
+      .......:::::..... ::::::::
+     ::::::::::::::::::. ::::::    ::::.
+    ::::::::::::::::::::: :::::.  .::::'
+           .....           ::::' :::::'
+          :::::            '::' :::::'
+ ........:::::               ' :::::::::::.
+:::::::::::::                 :::::::::::::
+ ::::::::::: ..              :::::
+     .::::: .:::            :::::
+    .:::::  :::::          '''''    .....
+    :::::   ':::::.  ......:::::::::::::'
+     :::     ::::::. ':::::::::::::::::'
+            .:::::::: '::::::::::
+           .::::''::::.     '::::.
+          .::::'   ::::.     '::::.
+         .::::      ::::      '::::."#,
+    },
+    Logo {
+        names: &[
+            "opensuse",
+            "opensuse-leap",
+            "opensuse-tumbleweed",
+            "suse",
+            "sles",
+        ],
+        colors: &[2, 7],
+        small: r#"  _______
+__|   __ \
+     / .\ \
+     \__/ |
+   _______|
+   \_______
+__________/"#,
+        large: r#"           .;ldkO0000Okdl;.
+       .;d00xl:^''''''^:ok00d;.
+     .d00l'                'o00d.
+   .d0Kd'  Okxol:;,.          :O0d.
+  .OKKKK0kOKKKKKKKKKKOxo:,      lKO.
+ ,0KKKKKKKKKKKKKKKK0P^,,,^dx:    ;00,
+.OKKKKKKKKKKKKKKKKk'.oOPPb.'0k.   cKO.
+:KKKKKKKKKKKKKKKKK: kKx..dd lKd   'OK:
+dKKKKKKKKKKKOx0KKKd ^0KKKO' kKKc   dKd
+dKKKKKKKKKKKK;.;oOKx,..^..;kKKK0.  dKd
+:KKKKKKKKKKKK0o;...^cdxxOK0O/^^'  .0K:
+ kKKKKKKKKKKKKKKK0x;,,......,;od  lKk
+ '0KKKKKKKKKKKKKKKKKKKKK00KKOo^  c00'
+  'kKKKOxddxkOO00000Okxoc;''   .dKk'
+    l0Ko.                    .c00l'
+     'l0Kk:.              .;xK0l'
+        'lkK0xl:;,,,,;:ldO0kl'
+            '^:ldxkkkkxdl:^'"#,
+    },
+    Logo {
+        names: &["solus"],
+        colors: &[4, 7, 1],
+        small: r#"    __________
+   /  /\      \
+  /  /  \      \
+ /  /    \      \
+/  /______\______\
+\_________________/
+ \_______________/"#,
+        large: r#"            -```````````
+          `-+/------------.`
+       .---:mNo---------------.
+     .-----yMMMy:---------------.
+   `------oMMMMMm/----------------`
+  .------/MMMMMMMN+----------------.
+ .------/NMMMMMMMMm-+/--------------.
+`------:NMMMMMMMMMMs-:sso:-----------`
+.-----:NMMMMMMMMMMMM:----/+++:-------.
+------dMMMMMMMMMMMMMy-------:/o+:-----
+-----sMMMMMMMMMMMMMMMo----------:-----
+----/MMMMMMMMMMMMMMMMM+---------------
+.---dMMMMMMMMMMMMMMMMMM+-------------.
+ ---:++++ossyyyyssoo++++::::::::::::--
+ .--------::--------------------------.
+  .---------------------------------.
+   `-----------------------------.`
+     `-------------------------.`
+        `.-------------------.`"#,
+    },
+    Logo {
+        names: &["freebsd"],
+        colors: &[1],
+        small: r#"/\,-'''''-,/\
+\_)       (_/
+|           |
+|           |
+ ;         ;
+  '-_____-'"#,
+        large: r#"```                        `
+  ` `.....---.......--.```   -/
+  +o   .--`         /y:`      +.
+   yo`:.            :o      `+-
+    y/               -/`   -o/
+   .-                  ::/sy+:.
+   /                     `--  /
+  `:                          :`
+  `:                          :`
+   /                          /
+   .-                        -.
+    --                      -.
+     `:`                  `:`
+       .--             `--.
+          .---.....----."#,
+    },
+    Logo {
+        names: &["openbsd"],
+        colors: &[3, 7, 6, 1, 8],
+        small: r#"      _____
+    \-     -/
+ \_/         \
+ |        O O |
+ |_  <   )  3 )
+ / \         /
+    /-_____-\"#,
+        large: r#"                                     _
+                                    (_)
+              |    .
+          .   |L  /|   .          _
+      _ . |\ _| \--+._/| .       (_)
+     / ||\| Y J  )   / |/| ./
+    J  |)'( |        ` F`.'/        _
+  -<|  F         __     .-<        (_)
+    | /       .-'. `.  /-. L___
+    J \      <    \  | | O\|.-'  _
+  _J \  .-    \/ O | | \  |F    (_)
+ '-F  -<_.     \   .-'  `-' L__
+__J  _   _.     >-'  )._.   |-'
+ `-|.'   /_.          \_|   F
+  /.-   .                _.<
+ /'    /.'             .'  `\
+  /L  /'   |/      _.-'-\
+ /'J       ___.---'\|
+   |\  .--' V  | `. `
+   |/`. `-.     `._)
+      / .-.\
+      \ (  `\
+       `.\"#,
+    },
+    Logo {
+        names: &["netbsd"],
+        colors: &[5, 7],
+        small: r#"()ncncncncncnc
+ \ncncncncncncnc
+  \=============
+   \
+    \
+     \"#,
+        large: r#"                     `-/oshdmNMNdhyo+:-`
+y/s+:-``    `.-:+oydNMMMMNhs/-``
+-m+NMMMMMMMMMMMMMMMMMMMNdhmNMMMmdhs+/-`
+ -m+NMMMMMMMMMMMMMMMMMMMMmy+:`
+  -N/dMMMMMMMMMMMMMMMds:`
+   -N/hMMMMMMMMMmho:`
+    -N/-:/++/:.`
+     :M+
+      :Mo
+       :Ms
+        :Ms
+         :Ms
+          :Ms
+           :Ms
+            :Ms
+             :Ms
+              :Ms"#,
+    },
+    Logo {
+        names: &["dragonfly", "dragonflybsd"],
+        colors: &[1, 7, 3],
+        small: r#"   ${c3},_,${c1}
+('-_${c3}|${c1}_-')
+ >--${c3}|${c1}--<
+(_-'${c3}|${c1}'-_)
+    ${c3}|
+    |
+    |"#,
+        large: r#",--,           |           ,--,
+|   `-,       ,^,       ,-'   |
+ `,    `-,   (/ \)   ,-'    ,'
+   `-,    `-,/   \,-'    ,-'
+      `------(   )------'
+  ,----------(   )----------,
+ |        _,-(   )-,_        |
+  `-,__,-'   \   /   `-,__,-'
+              | |
+              | |
+              | |
+              | |
+              | |
+              | |
+              `|'"#,
+    },
+    Logo {
+        names: &["linux", "tux"],
+        colors: &[8, 7, 3],
+        small: r#"    ___
+   (${c2}..${c1} |
+   (${c3}<>${c1} |
+  / __  \
+ ( /  \ /|
+${c3}_/\${c1} __)${c3}/_)
+\/-____\/"#,
+        large: r#"        #####
+       #######
+       ##O#O##
+       #######
+     ###########
+    #############
+   ###############
+   ################
+  #################
+#####################
+#####################
+  #################"#,
+    },
+    Logo {
+        names: &["rsfetch"],
+        colors: &[],
+        small: r#" ┬─┐┌─┐┌─┐┌─┐┌┬┐┌─┐┬ ┬
+ ├┬┘└─┐├┤ ├┤  │ │  ├─┤
+ ┴└─└─┘└  └─┘ ┴ └─┘┴ ┴"#,
+        large: r#" ┬─┐┌─┐┌─┐┌─┐┌┬┐┌─┐┬ ┬
+ ├┬┘└─┐├┤ ├┤  │ │  ├─┤
+ ┴└─└─┘└  └─┘ ┴ └─┘┴ ┴"#,
+    },
+];
+
+fn find_logo(name: &str) -> Option<&'static Logo> {
+    let name = name.trim().to_lowercase();
+    LOGOS.iter().find(|l| l.names.contains(&name.as_str()))
+}
+
+fn pick_size(logo: &Logo, size: &LogoSize) -> LogoArt {
+    let art = match size {
+        LogoSize::Small => logo.small.to_string(),
+        LogoSize::Large => logo.large.to_string(),
+    };
+
+    LogoArt {
+        art,
+        colors: logo.colors.iter().map(|c| Color::Ansi(*c)).collect(),
+    }
+}
+
+// the canonical names of all built-in logos.
+pub fn get_logo_names() -> Vec<&'static str> {
+    LOGOS.iter().map(|l| l.names[0]).collect()
+}
+
+// get a built-in logo by name (e.g. `--logo-name arch`).
+pub fn get_builtin_logo(name: &str, size: &LogoSize) -> Result<LogoArt> {
+    let logo = find_logo(name).context(UnknownLogo {
+        name: name.to_string(),
+    })?;
+
+    Ok(pick_size(logo, size))
+}
+
+// get the built-in logo for the first of the os-release
+// IDs that we have art for, trying `ID` before `ID_LIKE`.
+pub fn get_distro_logo(ids: &[String], size: &LogoSize) -> Option<LogoArt> {
+    ids.iter()
+        .filter_map(|id| find_logo(id))
+        .next()
+        .map(|logo| pick_size(logo, size))
+}
+
+// how to pick a logo out of a directory of logo files
+#[derive(PartialEq)]
+pub enum LogoPick {
+    // the file named after the distro, e.g. `arch.txt` or `debian.png`
+    Distro,
+    Random,
+}
+
+impl LogoPick {
+    pub fn from(pick: &str) -> LogoPick {
+        match pick {
+            "random" => LogoPick::Random,
+            _ => LogoPick::Distro,
+        }
+    }
+}
+
+// turn `--logo-seed` into a number: numbers are used as-is, anything
+// else (e.g. a hostname) is hashed, so it picks the same logo every time.
+pub fn parse_seed(seed: &str) -> u64 {
+    match seed.parse::<u64>() {
+        Ok(n) => n,
+        // FNV-1a
+        Err(_) => seed.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+        }),
+    }
+}
+
+// a random number from the seed, or from the clock and our PID if none.
+fn random(seed: Option<u64>) -> u64 {
+    let mut x = seed.unwrap_or_else(|| {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        nanos ^ (process::id() as u64) << 32
+    });
+
+    // splitmix64, so that close seeds still pick far apart
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+// pick a logo file out of `dir`. returns None if there is
+// no file for the distro, or no file at all.
+pub fn pick_logo_file(dir: &str, ids: &[String], pick: &LogoPick, seed: Option<u64>) -> Result<Option<String>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).context(ReadLogoDir)? {
+        let path = entry.context(ReadLogoDir)?.path();
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    // sorted, so that a seed always picks the same file
+    files.sort();
+
+    let file = match pick {
+        LogoPick::Distro => ids.iter().find_map(|id| {
+            files.iter().find(|f| {
+                f.file_stem()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|s| s.to_lowercase() == *id)
+            })
+        }),
+        LogoPick::Random if files.is_empty() => None,
+        LogoPick::Random => files.get(random(seed) as usize % files.len()),
+    };
+
+    Ok(file.map(|f| f.to_string_lossy().into_owned()))
+}
@@ -1,17 +1,31 @@
 use clap::{App, Arg};
 use log::error;
-use snafu::{OptionExt, ResultExt, Snafu};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 use std::fs::File;
+use std::path::Path;
 use std::result;
+use std::time::Duration;
 
 mod terminal;
 use crate::terminal::*;
+mod throughput;
+use crate::throughput::*;
 mod hostname;
 use crate::hostname::*;
+mod dns;
+use crate::dns::*;
 mod env;
 use crate::env::*;
+mod bar;
+use crate::bar::*;
+mod color;
+use crate::color::*;
 mod cpu;
 use crate::cpu::*;
+mod vpn;
+use crate::vpn::*;
+mod wifi;
+use crate::wifi::*;
 mod wmde;
 use crate::wmde::*;
 mod pkgs;
@@ -24,12 +38,18 @@ mod device;
 use crate::device::*;
 mod distro;
 use crate::distro::*;
+mod interfaces;
+use crate::interfaces::*;
 mod kernel;
 use crate::kernel::*;
+mod logo;
+use crate::logo::*;
 mod network;
 use crate::network::*;
 mod output;
 use crate::output::*;
+mod picture;
+use crate::picture::*;
 mod memory;
 use crate::memory::*;
 mod util;
@@ -47,6 +67,52 @@ pub enum Error {
     KernelVersion { source: std::io::Error },
     #[snafu(display("Unable to read the provided logo file: {}", source))]
     ReadLogo { source: std::io::Error },
+    #[snafu(display("Unable to read the provided logo directory: {}", source))]
+    ReadLogoDir { source: std::io::Error },
+    #[snafu(display("Unable to read the provided image: {}", source))]
+    ReadImage { source: image::ImageError },
+    #[snafu(display("Unable to encode the provided image: {}", source))]
+    EncodeImage { source: image::ImageError },
+    #[snafu(display(
+        "No built-in logo named \"{}\". Available logos are: {}.",
+        name,
+        get_logo_names().join(", ")
+    ))]
+    UnknownLogo { name: String },
+    #[snafu(display(
+        "Invalid color \"{}\". Use a number from 0 to 255, a name such as \"red\" or \"bright-red\", or a hex code such as \"#ff8800\".",
+        color
+    ))]
+    InvalidColor { color: String },
+    #[snafu(display("Invalid field color \"{}\". Use the field's title and a color, e.g. \"MEMORY=red\".", spec))]
+    InvalidFieldColor { spec: String },
+    #[snafu(display(
+        "No theme named \"{}\". Available themes are: {}.",
+        name,
+        get_theme_names().join(", ")
+    ))]
+    UnknownTheme { name: String },
+    #[snafu(display(
+        "No border style named \"{}\". Available styles are: {}.",
+        name,
+        get_border_style_names().join(", ")
+    ))]
+    UnknownBorderStyle { name: String },
+    #[snafu(display(
+        "Invalid border characters \"{}\". Exactly 8 are needed: horizontal, vertical, top-left, top-right, bottom-left, bottom-right, left tee and right tee.",
+        chars
+    ))]
+    InvalidBorderChars { chars: String },
+    #[snafu(display(
+        "Invalid bar characters \"{}\". Exactly 4 are needed: the left end, a filled cell, an empty cell and the right end.",
+        chars
+    ))]
+    InvalidBarChars { chars: String },
+    #[snafu(display(
+        "Invalid bar thresholds \"{}\". Use two percentages, the lower one first, such as \"60,85\".",
+        thresholds
+    ))]
+    InvalidThresholds { thresholds: String },
     #[snafu(display("Unable to retrieve uptime: {}", source))]
     Uptime { source: std::io::Error },
     #[snafu(display("Unable to determine home directory"))]
@@ -63,10 +129,46 @@ pub enum Error {
     EnvError { source: std::env::VarError },
     #[snafu(display("Unable to retrieve IP address: {}", source))]
     Hyper { source: hyper::Error },
+    #[snafu(display("Unable to list network interfaces: {}", source))]
+    Interfaces { source: std::io::Error },
+    #[snafu(display("Unable to read /proc/net/dev: {}", source))]
+    ReadNetDev { source: std::io::Error },
+    #[snafu(display("No network interface named \"{}\"", name))]
+    NoInterface { name: String },
+    #[snafu(display("Unable to list wireless interfaces: {}", source))]
+    ReadWireless { source: std::io::Error },
+    #[snafu(display("Unable to read /etc/resolv.conf: {}", source))]
+    ReadResolvConf { source: std::io::Error },
+    #[snafu(display("Unable to read /proc/net/route: {}", source))]
+    ReadRoutes { source: std::io::Error },
+    #[snafu(display("Invalid IP endpoint \"{}\", expected an http:// or https:// URL.", endpoint))]
+    InvalidEndpoint { endpoint: String },
+    #[snafu(display("Unable to retrieve IP address: {} refused the connection.", endpoint))]
+    IpRefused { endpoint: String, source: hyper::Error },
+    #[snafu(display("Unable to retrieve IP address: the endpoint answered {}", status))]
+    IpStatus { status: String },
+    #[snafu(display("Unable to retrieve IP address: \"{}\" is not an {} address.", body, expected))]
+    InvalidIpResponse { body: String, expected: String },
     #[snafu(display("Unable to retrieve package count."))]
     Pkgcount { source: std::io::Error },
-    #[snafu(display("Unable to retrive mpd information."))]
-    Mpc { source: std::io::Error },
+    #[snafu(display("Unable to connect to MPD: {}", source))]
+    MpdConnect { source: std::io::Error },
+    #[snafu(display("Unable to talk to MPD: {}", source))]
+    MpdIo { source: std::io::Error },
+    #[snafu(display("MPD returned an error: {}", message))]
+    MpdError { message: String },
+    #[snafu(display("Unable to talk to cmus: {}", source))]
+    Cmus { source: std::io::Error },
+    #[snafu(display("Unable to run mocp: {}", source))]
+    Moc { source: std::io::Error },
+    #[snafu(display("MOC returned an error: {}", message))]
+    MocError { message: String },
+    #[snafu(display("Unable to run dbus-send: {}", source))]
+    DBus { source: std::io::Error },
+    #[snafu(display("D-Bus returned an error: {}", message))]
+    DBusError { message: String },
+    #[snafu(display("No MPRIS player{} found.", if name.is_empty() { String::new() } else { format!(" named \"{}\"", name) }))]
+    NoPlayer { name: String },
     #[snafu(display("Unable to retrieve CPU information: {}", source))]
     CPUErr { source: std::io::Error },
     #[snafu(display(
@@ -160,6 +262,9 @@ async fn main() {
                          .short("H")
                          .long("hostname")
                          .help("Turn hostname on."))
+                    .arg(Arg::with_name("fqdn")
+                        .long("fqdn")
+                        .help("Show the fully qualified domain name along with the hostname, e.g. \"box (box.example.com)\"."))
                     .arg(Arg::with_name("host")
                         .short("h")
                         .long("host")
@@ -168,6 +273,56 @@ async fn main() {
                         .short("i")
                         .long("ip-address")
                         .help("Turn ip address display on."))
+                    .arg(Arg::with_name("interfaces")
+                        .short("I")
+                        .long("interfaces")
+                        .help("Turn the local network interfaces that are up on, with their addresses."))
+                    .arg(Arg::with_name("hide-virtual")
+                        .long("hide-virtual")
+                        .help("Hide loopback, bridges and the virtual interfaces plugged into them from the interfaces."))
+                    .arg(Arg::with_name("throughput")
+                        .short("T")
+                        .long("throughput")
+                        .help("Turn the current download and upload rates on, with the totals since boot."))
+                    .arg(Arg::with_name("wifi")
+                        .short("W")
+                        .long("wifi")
+                        .help("Turn the wireless interfaces on, with the network they are connected to, its signal and the bitrate."))
+                    .arg(Arg::with_name("gateway")
+                        .long("gateway")
+                        .help("Turn the default gateways on."))
+                    .arg(Arg::with_name("dns")
+                        .long("dns")
+                        .help("Turn the DNS servers and search domains on."))
+                    .arg(Arg::with_name("vpn")
+                        .long("vpn")
+                        .help("Turn the VPNs and tunnels that are up on, and whether the traffic to the internet goes through one."))
+                    .arg(Arg::with_name("throughput-interface")
+                        .long("throughput-interface")
+                        .value_name("NAME")
+                        .help("Measure the throughput of this interface, or of every one but loopback with \"all\". Defaults to the interface of the default route.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("throughput-interval")
+                        .long("throughput-interval")
+                        .value_name("SECONDS")
+                        .help("Specify how long to measure the throughput for. Defaults to 0.5.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("ip-endpoint")
+                        .long("ip-endpoint")
+                        .value_name("URL")
+                        .help("Specify the URL that answers with the public IP address as plain text. Defaults to \"https://ipecho.net/plain\".")
+                        .takes_value(true))
+                    .arg(Arg::with_name("ip-version")
+                        .long("ip-version")
+                        .value_name("VERSION")
+                        .possible_values(&["4", "6", "any"])
+                        .help("Look up the IPv4 or IPv6 address. Defaults to \"any\", the one the endpoint is reached over.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("ip-timeout")
+                        .long("ip-timeout")
+                        .value_name("SECONDS")
+                        .help("Specify how long to wait for the IP address before showing \"offline\". Defaults to 5.")
+                        .takes_value(true))
                     .arg(Arg::with_name("editor")
                         .short("e")
                         .long("editor")
@@ -214,7 +369,18 @@ async fn main() {
                         .short("m")
                         .long("music")
                         .value_name("SOURCE")
-                        .help("Choose where to get music info. The only supported options is \"mpd\".\n")
+                        .possible_values(&["mpd", "mpris", "cmus", "moc"])
+                        .help("Choose where to get music info: \"mpd\", \"cmus\", \"moc\", or \"mpris\" for the desktop players on the session bus (Spotify, Firefox, mpv, ...).\n")
+                        .takes_value(true))
+                    .arg(Arg::with_name("music-format")
+                        .long("music-format")
+                        .value_name("FORMAT")
+                        .help("Specify how to show the music, e.g. \"%state% %artist% - %title% %bar%\". See the man page for all the %tags%. Defaults to \"%state% %song%[ (%elapsed%/%duration%)]\".")
+                        .takes_value(true))
+                    .arg(Arg::with_name("player")
+                        .long("player")
+                        .value_name("NAME")
+                        .help("Use this MPRIS player (e.g. \"spotify\") instead of the one that is playing.")
                         .takes_value(true))
                     .arg(Arg::with_name("logo")
                         .short("l")
@@ -224,7 +390,177 @@ async fn main() {
                         .short("L")
                         .long("logofile")
                         .value_name("FILE")
-                        .help("Specify the file from which to read a custom ASCII logo.")
+                        .help("Specify the file from which to read a custom ASCII logo. PNG and JPEG files are drawn like --image. With a directory, one of its files is picked as per --logo-pick.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("logo-pick")
+                        .long("logo-pick")
+                        .value_name("HOW")
+                        .possible_values(&["distro", "random"])
+                        .help("Choose how to pick a logo when --logofile is a directory: the file named after the distro's ID (the default, e.g. \"arch.txt\"), or a random one. Tux is used if there is no such file.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("logo-seed")
+                        .long("logo-seed")
+                        .value_name("SEED")
+                        .help("Pick the same random logo every time for this seed: a number, or any text (e.g. a hostname).")
+                        .takes_value(true))
+                    .arg(Arg::with_name("image")
+                        .long("image")
+                        .value_name("FILE")
+                        .help("Draw a PNG or JPEG image as the logo, with Unicode half blocks or braille dots.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("image-width")
+                        .long("image-width")
+                        .value_name("COLUMNS")
+                        .help("Specify how many columns wide images are drawn. Defaults to 32.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("image-style")
+                        .long("image-style")
+                        .value_name("STYLE")
+                        .possible_values(&["blocks", "braille"])
+                        .help("Choose how images are drawn: \"blocks\" (the default) uses two truecolor pixels per cell, \"braille\" uses braille dots, and is also used when colors are off.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("image-protocol")
+                        .long("image-protocol")
+                        .value_name("PROTOCOL")
+                        .possible_values(&["auto", "kitty", "sixel", "text"])
+                        .help("Choose how images are shown: as real images with the kitty graphics protocol or Sixel, or drawn with text. \"auto\" (the default) goes by TERM and TERM_PROGRAM.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("logo-name")
+                        .long("logo-name")
+                        .value_name("NAME")
+                        .help("Use the built-in logo with this name instead of the one matching the current distro.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("logo-size")
+                        .long("logo-size")
+                        .value_name("SIZE")
+                        .possible_values(&["small", "large"])
+                        .help("Choose between the small and large variants of the built-in logos.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("logo-colors")
+                        .long("logo-colors")
+                        .value_name("COLORS")
+                        .help("Comma-separated colors to use for the ${c1}..${c6} placeholders in the logo (e.g. \"4,bright-white\").")
+                        .takes_value(true))
+                    .arg(Arg::with_name("color")
+                        .long("color")
+                        .value_name("WHEN")
+                        .possible_values(&["auto", "always", "never"])
+                        .help("Choose when to use colors and bold text. \"auto\" (the default) only uses them when printing to a terminal and NO_COLOR is not set.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("sections")
+                        .long("sections")
+                        .help("Group the fields into sections (Session, Software, Hardware and Network), each with a header."))
+                    .arg(Arg::with_name("overflow")
+                        .long("overflow")
+                        .value_name("MODE")
+                        .possible_values(&["truncate", "wrap", "none"])
+                        .help("Choose what to do with values too long for the terminal: cut them short with an ellipsis (the default), wrap them onto several lines, or leave them be.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("width")
+                        .long("width")
+                        .value_name("COLUMNS")
+                        .help("Lay the output out for this many columns instead of the width of the terminal.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("logo-position")
+                        .long("logo-position")
+                        .value_name("POSITION")
+                        .possible_values(&["left", "right", "top", "bottom"])
+                        .help("Choose where the logo goes. Defaults to \"top\" for the default style and \"left\" for the neofetch style. The logo is hidden when the terminal is too narrow for it.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("logo-gap")
+                        .long("logo-gap")
+                        .value_name("COLUMNS")
+                        .help("Specify the number of columns between the logo and the info when they are side by side. Defaults to 2.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("center-logo")
+                        .long("center-logo")
+                        .help("Center a logo shorter than the info vertically, when they are side by side."))
+                    .arg(Arg::with_name("palette")
+                        .long("palette")
+                        .help("Show the 8 normal and 8 bright terminal colors below the fields."))
+                    .arg(Arg::with_name("palette-glyph")
+                        .long("palette-glyph")
+                        .value_name("GLYPH")
+                        .help("Draw the color blocks with this glyph (e.g. \"█\" or \"●\") instead of background-colored spaces.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("palette-width")
+                        .long("palette-width")
+                        .value_name("WIDTH")
+                        .help("Specify how many glyphs or spaces make up each color block. Defaults to 3.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("bars")
+                        .long("bars")
+                        .value_name("MODE")
+                        .possible_values(&["append", "replace"])
+                        .help("Show usage bars for percentage-like fields (memory), either after the value or instead of it.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("bar-style")
+                        .long("bar-style")
+                        .value_name("STYLE")
+                        .possible_values(&["ascii", "blocks"])
+                        .help("Choose how bars are drawn: \"ascii\" (the default) looks like [#####-----], \"blocks\" uses Unicode partial blocks.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("bar-chars")
+                        .long("bar-chars")
+                        .value_name("CHARACTERS")
+                        .help("Draw bars with these 4 glyphs: the left end, a filled cell, an empty cell and the right end (e.g. \"[=-]\").")
+                        .takes_value(true))
+                    .arg(Arg::with_name("bar-width")
+                        .long("bar-width")
+                        .value_name("WIDTH")
+                        .help("Specify how many cells bars are made of. Defaults to 10.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("bar-thresholds")
+                        .long("bar-thresholds")
+                        .value_name("WARN,CRIT")
+                        .help("Specify the percentages from which bars turn from the first color to the second and third. Defaults to \"60,85\".")
+                        .takes_value(true))
+                    .arg(Arg::with_name("bar-colors")
+                        .long("bar-colors")
+                        .value_name("COLORS")
+                        .help("Comma-separated list of the 3 bar colors, below and above the thresholds. Defaults to \"green,yellow,red\".")
+                        .takes_value(true))
+                    .arg(Arg::with_name("theme")
+                        .long("theme")
+                        .value_name("NAME")
+                        .help("Choose a color theme for the output: \"default\", \"ocean\", \"forest\", \"sunset\", \"dracula\", \"gruvbox\" or \"nord\".")
+                        .takes_value(true))
+                    .arg(Arg::with_name("theme-color")
+                        .long("theme-color")
+                        .value_name("COLOR")
+                        .help("Use this color for every part of the output (keys, values, separators, borders and corners).")
+                        .takes_value(true))
+                    .arg(Arg::with_name("key-color")
+                        .long("key-color")
+                        .value_name("COLOR")
+                        .help("Specify the color of the field titles.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("value-color")
+                        .long("value-color")
+                        .value_name("COLOR")
+                        .help("Specify the color of the field values.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("separator-color")
+                        .long("separator-color")
+                        .value_name("COLOR")
+                        .help("Specify the color of the separator between titles and values.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("border-color")
+                        .long("border-color")
+                        .value_name("COLOR")
+                        .help("Specify the color of the border lines.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("corner-color")
+                        .long("corner-color")
+                        .value_name("COLOR")
+                        .help("Specify the color of the border corners.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("field-color")
+                        .long("field-color")
+                        .value_name("FIELD=COLOR")
+                        .help("Color the title and value of a single field, e.g. \"MEMORY=red\". Can be given more than once.")
+                        .multiple(true)
+                        .number_of_values(1)
                         .takes_value(true))
                     .arg(Arg::with_name("corners")
                         .short("C")
@@ -232,6 +568,22 @@ async fn main() {
                         .value_name("CHARACTER")
                         .help("Specify the corner character. Only used when borders are enabled.")
                         .takes_value(true))
+                    .arg(Arg::with_name("border-style")
+                        .long("border-style")
+                        .value_name("STYLE")
+                        .possible_values(&["default", "square", "rounded", "double", "heavy", "ascii"])
+                        .help("Choose the glyphs used for borders. \"ascii\" only uses +, - and |, for terminals without Unicode.")
+                        .takes_value(true))
+                    .arg(Arg::with_name("border-chars")
+                        .long("border-chars")
+                        .value_name("CHARACTERS")
+                        .help("Specify all border glyphs at once, in this order: horizontal, vertical, top-left, top-right, bottom-left, bottom-right, left tee, right tee (e.g. \"─│╭╮╰╯├┤\").")
+                        .takes_value(true))
+                    .arg(Arg::with_name("separator")
+                        .long("separator")
+                        .value_name("STRING")
+                        .help("Specify what goes between field titles and values in the default output style. Defaults to \" = \".")
+                        .takes_value(true))
                     .get_matches();
 
     if matches.is_present("credits") {
@@ -241,11 +593,11 @@ async fn main() {
         );
         println!(
             "Contributor:      Ki{}d Llaentenn     (Reddit: /u/kiedtl)     (Github: kiedtl)",
-            std::char::from_u32(235 as u32).unwrap()
+            std::char::from_u32(235).unwrap()
         );
         println!(
             "Contributor:      Lauren{}iu Nicola                           (Github: lnicola)\n",
-            std::char::from_u32(539 as u32).unwrap()
+            std::char::from_u32(539).unwrap()
         );
         println!("With thanks to:   \"/r/rust\", \"/u/tablair\", \"/u/kabocha_\", \"/u/DebuggingPanda\", for their contributions, and the tool \"neofetch\" for giving the inspiration to create this.");
         println!();
@@ -262,16 +614,56 @@ async fn main() {
         }
     };
 
-    let bold = !matches.is_present("no-bold");
+    // only use escape codes when printing to a terminal, unless told otherwise.
+    // see https://no-color.org for NO_COLOR.
+    let tty = stdout_is_tty();
+    let color = match matches.value_of("color").unwrap_or("auto") {
+        "always" => true,
+        "never" => false,
+        _ => tty && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()),
+    };
+
+    let bold = color && !matches.is_present("no-bold");
+
+    let overflow = match matches.value_of("overflow").unwrap_or("truncate") {
+        "wrap" => Overflow::Wrap,
+        "none" => Overflow::Ignore,
+        _ => Overflow::Truncate,
+    };
+
+    let term_width = match matches.value_of("width") {
+        Some(w) => match w.parse::<usize>() {
+            Ok(w) => Some(w),
+            Err(_) => {
+                error!("Invalid width \"{}\", using the width of the terminal.", w);
+                terminal_width()
+            }
+        },
+        None => terminal_width(),
+    };
     let caps = !matches.is_present("no-caps");
     let borders = !matches.is_present("no-borders");
 
     let temp = matches.is_present("farenheit");
 
     // For the options that require bools or other input.
-    let corners = matches.value_of("corners").unwrap_or("■");
+    let corners = matches.value_of("corners");
     let music = matches.value_of("music").unwrap_or("");
     let logofile = matches.value_of("logofile").unwrap_or("");
+    let logo_name = matches.value_of("logo-name");
+    let logo_size = LogoSize::from(matches.value_of("logo-size").unwrap_or("large"));
+    let logo_colors = matches.value_of("logo-colors");
+    let logo_pick = LogoPick::from(matches.value_of("logo-pick").unwrap_or("distro"));
+    let logo_seed = matches.value_of("logo-seed").map(parse_seed);
+    let image_style = ImageStyle::from(matches.value_of("image-style").unwrap_or("blocks"));
+    let image_protocol = ImageProtocol::from(matches.value_of("image-protocol").unwrap_or("auto"), tty && color);
+    let image_width = match matches.value_of("image-width").unwrap_or("32").parse::<u32>() {
+        Ok(w) if w > 0 => w,
+        _ => {
+            error!("Invalid image width, using 32.");
+            32
+        }
+    };
     let packages = matches.value_of("packages");
 
     let style;
@@ -283,27 +675,160 @@ async fn main() {
         style = OutputType::Rsfetch;
     }
 
-    let corner: char;
-    if matches.is_present("minimal") || !borders {
-        corner = ' ';
-    } else if borders {
-        corner = corners.chars().collect::<Vec<char>>()[0];
-    } else {
-        corner = '■';
+    let border_style = match matches.value_of("border-chars") {
+        Some(chars) => BorderStyle::custom(chars),
+        None => BorderStyle::preset(matches.value_of("border-style").unwrap_or("default")),
+    };
+    let mut border_style = match border_style {
+        Ok(b) => b,
+        Err(e) => {
+            error!("{}", e);
+            BorderStyle::preset("default").unwrap()
+        }
+    };
+
+    if let Some(corner) = corners.and_then(|c| c.chars().next()) {
+        border_style.set_corners(corner);
+    }
+
+    // start from the preset, then apply the global color,
+    // and then the colors of the individual parts.
+    let mut theme = match Theme::preset(matches.value_of("theme").unwrap_or("default")) {
+        Ok(t) => t,
+        Err(e) => {
+            error!("{}", e);
+            Theme::default()
+        }
+    };
+
+    if let Some(color) = matches.value_of("theme-color") {
+        match Color::parse(color) {
+            Ok(c) => theme.set_all(c),
+            Err(e) => error!("{}", e),
+        }
+    }
+
+    for (arg, part) in &mut [
+        ("key-color", &mut theme.key),
+        ("value-color", &mut theme.value),
+        ("separator-color", &mut theme.separator),
+        ("border-color", &mut theme.border),
+        ("corner-color", &mut theme.corner),
+    ] {
+        if let Some(color) = matches.value_of(*arg) {
+            match Color::parse(color) {
+                Ok(c) => **part = Some(c),
+                Err(e) => error!("{}", e),
+            }
+        }
+    }
+
+    for spec in matches.values_of("field-color").into_iter().flatten() {
+        if let Err(e) = theme.set_field(spec) {
+            error!("{}", e);
+        }
     }
 
+    if !color {
+        theme = Theme::default();
+    }
+
+    let palette = if matches.is_present("palette") {
+        let width = match matches.value_of("palette-width").unwrap_or("3").parse::<usize>() {
+            Ok(w) => w,
+            Err(_) => {
+                error!("Invalid palette width, using 3.");
+                3
+            }
+        };
+
+        Some(Palette {
+            glyph: matches.value_of("palette-glyph").map(|g| g.to_string()),
+            width,
+        })
+    } else {
+        None
+    };
+
+    // bars are also used for the progress of the music
+    let bars = matches.is_present("bars");
+    let bar = {
+        let mode = BarMode::from(matches.value_of("bars").unwrap_or("append"));
+        let mut bar = Bar::new(mode, matches.value_of("bar-style").unwrap_or("ascii"));
+
+        if let Some(chars) = matches.value_of("bar-chars") {
+            if let Err(e) = bar.set_chars(chars) {
+                error!("{}", e);
+            }
+        }
+
+        if let Some(w) = matches.value_of("bar-width") {
+            match w.parse::<usize>() {
+                Ok(w) => bar.width = w,
+                Err(_) => error!("Invalid bar width \"{}\", using {}.", w, bar.width),
+            }
+        }
+
+        if let Some(thresholds) = matches.value_of("bar-thresholds") {
+            if let Err(e) = bar.set_thresholds(thresholds) {
+                error!("{}", e);
+            }
+        }
+
+        if let Some(colors) = matches.value_of("bar-colors") {
+            match parse_colors(colors) {
+                Ok(c) => bar.colors = c,
+                Err(e) => error!("{}", e),
+            }
+        }
+
+        if !color {
+            bar.colors = Vec::new();
+        }
+
+        bar
+    };
+
+    let logo_gap = match matches.value_of("logo-gap").unwrap_or("2").parse::<usize>() {
+        Ok(g) => g,
+        Err(_) => {
+            error!("Invalid logo gap, using 2.");
+            2
+        }
+    };
+
     let opts = OutputOptions {
         output_type: style.clone(),
         caps,
         bold,
+        color,
+        tty,
+        term_width,
+        overflow,
+        sections: matches.is_present("sections"),
         use_borders: borders,
-        borders: corner,
+        borders: border_style,
+        separator: matches.value_of("separator").unwrap_or(" = ").to_string(),
+        theme,
+        palette,
+        logo_position: matches.value_of("logo-position").map(LogoPosition::from),
+        logo_gap,
+        center_logo: matches.is_present("center-logo"),
     };
 
     let cpu_opts = CPUOptions {
         farenheit: temp
     };
 
+    let throughput_interval = matches.value_of("throughput-interval").unwrap_or("0.5").parse::<f64>();
+    let throughput_interval = match throughput_interval.map(Duration::try_from_secs_f64) {
+        Ok(Ok(t)) if !t.is_zero() => t,
+        _ => {
+            error!("Invalid throughput interval, using 0.5.");
+            Duration::from_millis(500)
+        }
+    };
+
     //let format;
     // env: variable that holds $USER, $SHELL, and $VISUAL or $EDITOR.
     let mut env = EnvInfo::new();
@@ -323,19 +848,91 @@ async fn main() {
 
     // Determine the logo to use.
     if matches.is_present("logo") {
-        let mut logo: String = "".to_owned();
-        if !logofile.is_empty() {
-            match get_logo_from_file(logofile.to_owned()) {
-                Ok(l) => logo = l,
+        let mut distro = DistroInfo::new();
+        if let Err(e) = distro.get() {
+            error!("{}", e);
+        }
+
+        // logos without colors of their own use os-release's ANSI_COLOR
+        let mut logo = LogoArt {
+            art: String::new(),
+            colors: distro.ansi_color().map(Color::Sgr).into_iter().collect(),
+        };
+
+        // a directory of logos: use one of its files, or Tux
+        let mut logofile = logofile.to_string();
+        let mut tux = false;
+        if Path::new(&logofile).is_dir() {
+            match pick_logo_file(&logofile, &distro.ids(), &logo_pick, logo_seed) {
+                Ok(Some(file)) => logofile = file,
+                Ok(None) => tux = true,
+                Err(e) => {
+                    error!("{}", e);
+                    tux = true;
+                }
+            }
+        }
+
+        let image = matches.value_of("image").or(Some(logofile.as_str()).filter(|f| is_image(f)));
+        if let Some(image) = image {
+            match render_image(image, image_width, &image_style, &image_protocol, color) {
+                Ok(art) => {
+                    // images come with their own colors
+                    logo.art = art;
+                    logo.colors = Vec::new();
+                    if image_protocol != ImageProtocol::Text {
+                        writer.reserve_space();
+                    }
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    logo.art = get_default_logo(&style);
+                }
+            }
+        } else if tux {
+            logo.art = get_default_logo(&OutputType::Neofetch);
+        } else if !logofile.is_empty() {
+            match get_logo_from_file(logofile) {
+                Ok(l) => logo.art = l,
                 Err(e) => error!("{:?}", e),
             }
+        } else if let Some(name) = logo_name {
+            match get_builtin_logo(name, &logo_size) {
+                Ok(l) => logo = l,
+                Err(e) => {
+                    error!("{}", e);
+                    logo.art = get_default_logo(&style);
+                }
+            }
         } else {
-            logo = get_default_logo(&style);
+            // pick the logo matching os-release's ID or ID_LIKE
+            match get_distro_logo(&distro.ids(), &logo_size) {
+                Some(l) => logo = l,
+                None => logo.art = get_default_logo(&style),
+            }
+        }
+
+        // colors given on the command line replace the logo's own
+        if let Some(colors) = logo_colors {
+            match parse_colors(colors) {
+                Ok(colors) => {
+                    for (i, color) in colors.into_iter().enumerate() {
+                        if i < logo.colors.len() {
+                            logo.colors[i] = color;
+                        } else {
+                            logo.colors.push(color);
+                        }
+                    }
+                }
+                Err(e) => error!("{}", e),
+            }
         }
-        writer.ascii(logo);
+
+        writer.ascii(logo.art, logo.colors);
     }
 
     if matches.is_present("user") || matches.is_present("hostname") {
+        writer.section("Session");
         let mut hostname = Hostname::new();
         let mut user = "".to_owned();
         let mut host = "".to_owned();
@@ -354,6 +951,9 @@ async fn main() {
             }
         }
 
+        // the fqdn goes after the user's `@`, or along with the hostname
+        let fqdn = if matches.is_present("fqdn") { hostname.fqdn() } else { None };
+
         if matches.is_present("userat") {
             let mut userstr: String = "".to_owned();
             if matches.is_present("user") {
@@ -369,6 +969,7 @@ async fn main() {
                     userstr = format!("{}@", userstr);
                 }
 
+                let host = fqdn.unwrap_or(host);
                 if bold && (style != OutputType::Rsfetch) {
                     userstr = format!("{}{}[1m{}{}[0m", userstr, 27 as char, host, 27 as char);
                 } else {
@@ -387,12 +988,16 @@ async fn main() {
             }
 
             if matches.is_present("hostname") {
-                writer.add("HOSTNAME", &host);
+                match fqdn {
+                    Some(fqdn) => writer.add("HOSTNAME", &format!("{} ({})", host, fqdn)),
+                    None => writer.add("HOSTNAME", &host),
+                }
             }
         }
     }
 
     if matches.is_present("distro") {
+        writer.section("Software");
         let mut distro = DistroInfo::new();
         match distro.get() {
             //Ok(()) => writer.add("DISTRO", &distro.format()),
@@ -402,6 +1007,7 @@ async fn main() {
     }
 
     if matches.is_present("host") {
+        writer.section("Hardware");
         let mut device = DeviceInfo::new();
         match device.get() {
             Ok(()) => writer.add("HOST", &device.format()),
@@ -410,6 +1016,7 @@ async fn main() {
     }
 
     if matches.is_present("uptime") {
+        writer.section("Software");
         let mut uptime = UptimeInfo::new();
         match uptime.get() {
             Ok(()) => writer.add("UPTIME", &uptime.format()),
@@ -418,6 +1025,7 @@ async fn main() {
     }
 
     if matches.is_present("kernel") {
+        writer.section("Software");
         let mut kernel = KernelInfo::new();
         match kernel.get() {
             Ok(()) => writer.add("KERNEL", &kernel.format()),
@@ -425,6 +1033,7 @@ async fn main() {
         }
     }
     if matches.is_present("wm") {
+        writer.section("Session");
         let mut wmde = WMDEInfo::new();
         match wmde.get() {
             Ok(()) => writer.add("WM/DE", &wmde.format()),
@@ -439,6 +1048,7 @@ async fn main() {
     }
 
     if matches.is_present("editor") {
+        writer.section("Session");
         match env.get(EnvItem::Editor) {
             Ok(()) => writer.add("EDITOR", &env.format(EnvItem::Editor)),
             Err(e) => error!("{}", e),
@@ -446,6 +1056,7 @@ async fn main() {
     }
 
     if matches.is_present("shell") {
+        writer.section("Session");
         match env.get(EnvItem::Shell) {
             Ok(()) => writer.add("SHELL", &env.format(EnvItem::Shell)),
             Err(e) => error!("{}", e),
@@ -453,6 +1064,7 @@ async fn main() {
     }
 
     if matches.is_present("terminal") {
+        writer.section("Session");
         let mut term = Terminal::new();
         match term.get() {
             Ok(()) => writer.add("TERMINAL", &term.format()),
@@ -461,6 +1073,7 @@ async fn main() {
     }
 
     if matches.is_present("cpu") {
+        writer.section("Hardware");
         let mut cpu = CPUInfo::new(cpu_opts);
         match cpu.get(&os) {
             Ok(()) => writer.add("CPU", &cpu.format()),
@@ -469,14 +1082,84 @@ async fn main() {
     }
 
     if matches.is_present("ip-address") {
-        let mut ip = NetworkInfo::new();
+        writer.section("Network");
+        let timeout = matches.value_of("ip-timeout").unwrap_or("5").parse::<f64>();
+        let timeout = match timeout.map(Duration::try_from_secs_f64) {
+            Ok(Ok(t)) => t,
+            _ => {
+                error!("Invalid IP timeout, using 5.");
+                Duration::from_secs(5)
+            }
+        };
+        let network_opts = NetworkOptions {
+            endpoint: matches.value_of("ip-endpoint").map(String::from),
+            version: IpVersion::from(matches.value_of("ip-version").unwrap_or("any")),
+            timeout,
+        };
+
+        let mut ip = NetworkInfo::new(network_opts);
         match ip.get().await {
             Ok(()) => writer.add("IP ADDRESS", &ip.format()),
             Err(e) => error!("{}", e),
         }
     }
 
+    if matches.is_present("interfaces") {
+        writer.section("Network");
+        let mut interfaces = InterfaceInfo::new();
+        match interfaces.get(matches.is_present("hide-virtual")) {
+            Ok(()) => writer.add("INTERFACES", &interfaces.format()),
+            Err(e) => error!("{}", e),
+        }
+    }
+
+    if matches.is_present("wifi") {
+        writer.section("Network");
+        let mut wifi = WifiInfo::new();
+        match wifi.get() {
+            Ok(()) => writer.add("WI-FI", &wifi.format()),
+            Err(e) => error!("{}", e),
+        }
+    }
+
+    if matches.is_present("gateway") {
+        writer.section("Network");
+        let mut gateway = GatewayInfo::new();
+        match gateway.get() {
+            Ok(()) => writer.add("GATEWAY", &gateway.format()),
+            Err(e) => error!("{}", e),
+        }
+    }
+
+    if matches.is_present("dns") {
+        writer.section("Network");
+        let mut dns = DnsInfo::new();
+        match dns.get() {
+            Ok(()) => writer.add("DNS", &dns.format()),
+            Err(e) => error!("{}", e),
+        }
+    }
+
+    if matches.is_present("vpn") {
+        writer.section("Network");
+        let mut vpn = VpnInfo::new();
+        match vpn.get() {
+            Ok(()) => writer.add("VPN", &vpn.format()),
+            Err(e) => error!("{}", e),
+        }
+    }
+
+    if matches.is_present("throughput") {
+        writer.section("Network");
+        let mut throughput = ThroughputInfo::new();
+        match throughput.get(matches.value_of("throughput-interface"), throughput_interval) {
+            Ok(()) => writer.add("THROUGHPUT", &throughput.format()),
+            Err(e) => error!("{}", e),
+        }
+    }
+
     if let Some(packages) = packages {
+        writer.section("Software");
         let mut pkgs = PkgInfo::new();
         pkgs.set_manager(packages);
 
@@ -487,18 +1170,33 @@ async fn main() {
     }
 
     if matches.is_present("memory") {
+        writer.section("Hardware");
         let mut mem = RAMInfo::new();
         match mem.get(&os) {
-            Ok(()) => writer.add("MEMORY", &mem.format()),
+            Ok(()) => match (bars, mem.percent()) {
+                (true, Some(percent)) => writer.add("MEMORY", &bar.apply(&mem.format(), percent)),
+                _ => writer.add("MEMORY", &mem.format()),
+            },
             Err(e) => error!("{}", e),
         }
     }
 
-    if music == "mpd" {
-        let mut mpd = MusicInfo::new();
+    if !music.is_empty() {
+        writer.section("Session");
+        let mut song = MusicInfo::new();
+
+        let got = match music {
+            "mpris" => song.get_mpris(matches.value_of("player")),
+            "cmus" => song.get_cmus(),
+            "moc" => song.get_moc(),
+            _ => song.get_mpd(),
+        };
 
-        match mpd.get() {
-            Ok(()) => writer.add("MUSIC (MPD)", &mpd.format()),
+        match got {
+            Ok(()) => writer.add(
+                &format!("MUSIC ({})", music.to_uppercase()),
+                &song.format(matches.value_of("music-format").unwrap_or(DEFAULT_MUSIC_FORMAT), &bar),
+            ),
             Err(e) => error!("{}", e),
         }
     }
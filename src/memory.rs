@@ -80,7 +80,7 @@ impl RAMInfo {
                 .for_each(|b| buffer.push(*b as char));
             used = buffer
                 .split('\n')
-                .last()
+                .next_back()
                 .unwrap()
                 .split(' ')
                 .nth(2)
@@ -106,10 +106,6 @@ impl RAMInfo {
             buffer = buffer.trim().replace("\n", "");
             total = buffer.parse::<u64>().unwrap() as f64;
 
-            let pagesize: f64;
-            let inactive: f64;
-            let free: f64;
-            let cache: f64;
             buffer = "".to_owned();
 
             Command::new("sysctl")
@@ -125,10 +121,10 @@ impl RAMInfo {
                 .for_each(|b| buffer.push(*b as char));
 
             let info = buffer.split('\n').collect::<Vec<&str>>();
-            pagesize = info[0].parse::<f64>().unwrap();
-            inactive = info[1].parse::<f64>().unwrap();
-            free = info[2].parse::<f64>().unwrap();
-            cache = info[3].parse::<f64>().unwrap();
+            let pagesize = info[0].parse::<f64>().unwrap();
+            let inactive = info[1].parse::<f64>().unwrap();
+            let free = info[2].parse::<f64>().unwrap();
+            let cache = info[3].parse::<f64>().unwrap();
 
             self.total = Some(total / 1024_f64 / 1024_f64);
             self.used = Some(
@@ -164,9 +160,8 @@ impl RAMInfo {
                             .parse::<f64>()
                             .unwrap();
 
-                        match key {
-                            "MemFree" => free = val,
-                            &_ => (),
+                        if key == "MemFree" {
+                            free = val;
                         }
                     }
                 });
@@ -183,6 +178,14 @@ impl RAMInfo {
         }
     }
 
+    // how much of the memory is used, from 0 to 100.
+    pub fn percent(&self) -> Option<f64> {
+        match (self.used, self.total) {
+            (Some(u), Some(t)) if t > 0_f64 => Some(u / t * 100_f64),
+            _ => None,
+        }
+    }
+
     pub fn format(&self) -> String {
         let mut info = String::new();
 
@@ -1,35 +1,922 @@
 use crate::*;
+use log::debug;
+use std::collections::HashMap;
+use std::env;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
 use std::process::Command;
+use std::time::Duration;
+
+// how long to wait for the player before giving up
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+// what `--music-format` defaults to
+pub const DEFAULT_MUSIC_FORMAT: &str = "%state% %song%[ (%elapsed%/%duration%)]";
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum PlayState {
+    Playing,
+    Paused,
+    Stopped,
+}
 
 pub struct MusicInfo {
-    data: String,
+    pub artist: String,
+    pub album: String,
+    pub title: String,
+    pub date: String,
+    // the file (or URL) being played, for songs without tags
+    pub file: String,
+    // in seconds
+    pub elapsed: Option<f64>,
+    pub duration: Option<f64>,
+    pub state: PlayState,
+}
+
+trait Stream: Read + Write {}
+impl Stream for TcpStream {}
+impl Stream for UnixStream {}
+
+// a connection to MPD, talking its text protocol:
+// one command per line, answered by `key: value` lines and `OK`.
+struct Mpd {
+    conn: BufReader<Box<dyn Stream>>,
+}
+
+impl Mpd {
+    // connect to where MPD_HOST and MPD_PORT say, like mpc does:
+    // MPD_HOST is a hostname or a socket path, optionally
+    // preceded by `password@`. without it, the usual sockets
+    // are tried before localhost.
+    fn connect() -> Result<Mpd> {
+        let port = env::var("MPD_PORT")
+            .ok()
+            .and_then(|p| p.parse::<u16>().ok())
+            .unwrap_or(6600);
+        let host = env::var("MPD_HOST").ok();
+        let (password, host) = match host.as_deref() {
+            Some(h) => match h.find('@') {
+                Some(i) if i > 0 => (Some(&h[..i]), Some(&h[i + 1..])),
+                _ => (None, Some(h)),
+            },
+            None => (None, None),
+        };
+
+        let stream: Box<dyn Stream> = match host {
+            Some(path) if path.starts_with('/') => Box::new(unix(path).context(MpdConnect)?),
+            Some(host) => Box::new(Mpd::tcp(host, port).context(MpdConnect)?),
+            None => {
+                let mut sockets = vec!["/run/mpd/socket".to_string()];
+                if let Ok(dir) = env::var("XDG_RUNTIME_DIR") {
+                    sockets.insert(0, format!("{}/mpd/socket", dir));
+                }
+
+                match unix_any(&sockets) {
+                    Ok(s) => Box::new(s),
+                    Err(_) => Box::new(Mpd::tcp("localhost", port).context(MpdConnect)?),
+                }
+            }
+        };
+
+        Mpd::open(stream, password)
+    }
+
+    // start talking to MPD on a connection, logging in with the password if any.
+    fn open(stream: Box<dyn Stream>, password: Option<&str>) -> Result<Mpd> {
+        let mut mpd = Mpd {
+            conn: BufReader::new(stream),
+        };
+
+        // MPD greets us with `OK MPD <version>`
+        let greeting = mpd.line()?;
+        ensure!(
+            greeting.starts_with("OK MPD "),
+            MpdError {
+                message: format!("unexpected greeting \"{}\"", greeting)
+            }
+        );
+
+        if let Some(password) = password {
+            mpd.command(&format!("password {}", quote(password)))?;
+        }
+
+        Ok(mpd)
+    }
+
+    fn tcp(host: &str, port: u16) -> std::io::Result<TcpStream> {
+        let mut last = None;
+        for addr in (host, port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, TIMEOUT) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(TIMEOUT))?;
+                    stream.set_write_timeout(Some(TIMEOUT))?;
+                    return Ok(stream);
+                }
+                Err(e) => last = Some(e),
+            }
+        }
+
+        Err(last.unwrap_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound)))
+    }
+
+    fn line(&mut self) -> Result<String> {
+        let mut line = String::new();
+        let read = self.conn.read_line(&mut line).context(MpdIo)?;
+        ensure!(
+            read > 0,
+            MpdError {
+                message: "connection closed".to_string()
+            }
+        );
+
+        Ok(line.trim_end_matches(['\n', '\r']).to_string())
+    }
+
+    // send a command, and get the `key: value` pairs of the answer.
+    fn command(&mut self, command: &str) -> Result<Vec<(String, String)>> {
+        let conn = self.conn.get_mut();
+        conn.write_all(format!("{}\n", command).as_bytes()).context(MpdIo)?;
+        conn.flush().context(MpdIo)?;
+
+        let mut pairs = Vec::new();
+        loop {
+            let line = self.line()?;
+            if line == "OK" {
+                return Ok(pairs);
+            }
+
+            // errors look like `ACK [5@0] {password} incorrect password`
+            ensure!(
+                !line.starts_with("ACK "),
+                MpdError {
+                    message: line[4..].to_string()
+                }
+            );
+
+            if let Some((key, val)) = line.split_once(": ") {
+                pairs.push((key.to_string(), val.to_string()));
+            }
+        }
+    }
+}
+
+fn unix(path: &str) -> std::io::Result<UnixStream> {
+    let stream = UnixStream::connect(path)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    Ok(stream)
+}
+
+// connect to the first of the sockets that works.
+fn unix_any(paths: &[String]) -> std::io::Result<UnixStream> {
+    let mut last = None;
+    for path in paths {
+        match unix(path) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last = Some(e),
+        }
+    }
+
+    Err(last.unwrap_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound)))
+}
+
+// call a method on the session bus with `dbus-send`,
+// and get its printed reply.
+fn dbus_call(dest: &str, path: &str, method: &str, args: &[&str]) -> Result<String> {
+    let out = Command::new("dbus-send")
+        .arg("--session")
+        .arg("--print-reply")
+        .arg(format!("--reply-timeout={}", TIMEOUT.as_millis()))
+        .arg(format!("--dest={}", dest))
+        .arg(path)
+        .arg(method)
+        .args(args)
+        .output()
+        .context(DBus)?;
+
+    ensure!(
+        out.status.success(),
+        DBusError {
+            message: String::from_utf8_lossy(&out.stderr).trim().to_string()
+        }
+    );
+
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+// the value printed on a line of `dbus-send` output,
+// e.g. `string "Roygbiv"`, `int64 151200000` or `variant double 1`.
+fn dbus_value(line: &str) -> String {
+    let line = line.trim_start_matches("variant").trim();
+    match (line.find('"'), line.rfind('"')) {
+        (Some(start), Some(end)) if start < end => line[start + 1..end].to_string(),
+        _ => line.split_whitespace().nth(1).unwrap_or("").to_string(),
+    }
+}
+
+// the bus names in the reply to ListNames.
+fn parse_names(reply: &str) -> Vec<String> {
+    reply
+        .lines()
+        .map(str::trim)
+        .filter(|l| l.starts_with("string"))
+        .map(dbus_value)
+        .collect()
+}
+
+// the properties of an MPRIS player.
+fn mpris_properties(player: &str) -> Result<HashMap<String, Vec<String>>> {
+    let reply = dbus_call(
+        player,
+        "/org/mpris/MediaPlayer2",
+        "org.freedesktop.DBus.Properties.GetAll",
+        &["string:org.mpris.MediaPlayer2.Player"],
+    )?;
+
+    Ok(parse_properties(&reply))
+}
+
+// the properties in the reply to GetAll, flattened: the keys of the
+// nested Metadata dict end up next to PlaybackStatus and Position,
+// and arrays (e.g. of artists) become several values for a key.
+fn parse_properties(reply: &str) -> HashMap<String, Vec<String>> {
+    let mut props: HashMap<String, Vec<String>> = HashMap::new();
+    let mut key = String::new();
+    let mut expect_key = false;
+    for line in reply.lines().skip(1).map(str::trim) {
+        if line.starts_with("dict entry(") {
+            expect_key = true;
+        } else if expect_key {
+            key = dbus_value(line);
+            expect_key = false;
+        } else if line.starts_with("variant") || line.starts_with("string") {
+            // containers are followed by their values on the next lines
+            if !line.ends_with('[') && !line.ends_with('(') {
+                props.entry(key.clone()).or_default().push(dbus_value(line));
+            }
+        }
+    }
+
+    props
+}
+
+// quote an argument for MPD.
+fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
 }
 
 impl MusicInfo {
     pub fn new() -> MusicInfo {
         MusicInfo {
-            data: String::new(),
+            artist: String::new(),
+            album: String::new(),
+            title: String::new(),
+            date: String::new(),
+            file: String::new(),
+            elapsed: None,
+            duration: None,
+            state: PlayState::Stopped,
+        }
+    }
+
+    pub fn get_mpd(&mut self) -> Result<()> {
+        let mut mpd = Mpd::connect()?;
+
+        for (key, val) in mpd.command("currentsong")? {
+            match key.as_str() {
+                "Artist" => self.artist = val,
+                "Album" => self.album = val,
+                "Title" => self.title = val,
+                "Date" => self.date = val,
+                "file" => self.file = val,
+                &_ => (),
+            }
+        }
+
+        for (key, val) in mpd.command("status")? {
+            match key.as_str() {
+                "state" => {
+                    self.state = match val.as_str() {
+                        "play" => PlayState::Playing,
+                        "pause" => PlayState::Paused,
+                        _ => PlayState::Stopped,
+                    }
+                }
+                "elapsed" => self.elapsed = val.parse::<f64>().ok(),
+                "duration" => self.duration = val.parse::<f64>().ok(),
+                // older versions only have `time: <elapsed>:<duration>`
+                "time" => {
+                    if let Some((elapsed, duration)) = val.split_once(':') {
+                        self.elapsed = self.elapsed.or_else(|| elapsed.parse::<f64>().ok());
+                        self.duration = self.duration.or_else(|| duration.parse::<f64>().ok());
+                    }
+                }
+                &_ => (),
+            }
+        }
+
+        // `close` has no answer, and we're done either way
+        let _ = mpd.conn.get_mut().write_all(b"close\n");
+
+        Ok(())
+    }
+
+    // get the song from an MPRIS player on the session bus: the one
+    // whose name starts with `player` (e.g. "spotify" or "firefox"),
+    // or else the one that is playing, or else the first one.
+    pub fn get_mpris(&mut self, player: Option<&str>) -> Result<()> {
+        let names = dbus_call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus.ListNames",
+            &[],
+        )?;
+
+        let prefix = "org.mpris.MediaPlayer2.";
+        let mut players = parse_names(&names)
+            .into_iter()
+            .filter(|n| n.starts_with(prefix))
+            .collect::<Vec<String>>();
+        players.sort();
+
+        if let Some(name) = player {
+            players.retain(|p| p[prefix.len()..].starts_with(name));
+        }
+
+        let mut found = None;
+        for name in &players {
+            // a player that doesn't answer shouldn't hide the others
+            let props = match mpris_properties(name) {
+                Ok(p) => p,
+                Err(e) => {
+                    debug!("Skipping {}: {}", name, e);
+                    continue;
+                }
+            };
+            let playing = props.get("PlaybackStatus").and_then(|s| s.first()).map(String::as_str) == Some("Playing");
+            if found.is_none() || playing {
+                found = Some(props);
+            }
+            if playing {
+                break;
+            }
+        }
+
+        let props = found.context(NoPlayer {
+            name: player.unwrap_or("").to_string(),
+        })?;
+        let first = |key: &str| props.get(key).and_then(|v| v.first()).cloned().unwrap_or_default();
+        // times are in microseconds
+        let seconds = |key: &str| first(key).parse::<f64>().ok().map(|t| t / 1_000_000_f64);
+
+        self.artist = props.get("xesam:artist").map(|a| a.join(", ")).unwrap_or_default();
+        self.album = first("xesam:album");
+        self.title = first("xesam:title");
+        // e.g. `2007-01-01T00:00:00`, of which the year will do
+        self.date = first("xesam:contentCreated").split('-').next().unwrap_or("").to_string();
+        self.file = first("xesam:url");
+        self.elapsed = seconds("Position");
+        self.duration = seconds("mpris:length");
+        self.state = match first("PlaybackStatus").as_str() {
+            "Playing" => PlayState::Playing,
+            "Paused" => PlayState::Paused,
+            _ => PlayState::Stopped,
+        };
+
+        Ok(())
+    }
+
+    // get the song from cmus, by sending `status` to its socket. the answer
+    // is `key value` lines (e.g. `status playing` or `tag artist Queen`),
+    // ending with an empty one.
+    pub fn get_cmus(&mut self) -> Result<()> {
+        let mut sockets = Vec::new();
+        if let Ok(socket) = env::var("CMUS_SOCKET") {
+            sockets.push(socket);
+        }
+        if let Ok(dir) = env::var("XDG_RUNTIME_DIR") {
+            sockets.push(format!("{}/cmus-socket", dir));
+        }
+        let config = env::var("XDG_CONFIG_HOME")
+            .ok()
+            .or_else(|| dirs::home_dir().map(|h| format!("{}/.config", h.display())));
+        if let Some(config) = config {
+            sockets.push(format!("{}/cmus/socket", config));
+        }
+
+        let mut stream = unix_any(&sockets).context(Cmus)?;
+        stream.write_all(b"status\n").context(Cmus)?;
+        for line in BufReader::new(stream).lines() {
+            let line = line.context(Cmus)?;
+            if line.is_empty() {
+                break;
+            }
+
+            let (key, val) = line.split_once(' ').unwrap_or((&line, ""));
+            let (key, val) = match key {
+                "tag" => val.split_once(' ').unwrap_or((val, "")),
+                _ => (key, val),
+            };
+
+            match key {
+                "status" => {
+                    self.state = match val {
+                        "playing" => PlayState::Playing,
+                        "paused" => PlayState::Paused,
+                        _ => PlayState::Stopped,
+                    }
+                }
+                "file" => self.file = val.to_string(),
+                // the title of what's on, for internet radio
+                "stream" => self.title = val.to_string(),
+                "duration" => self.duration = val.parse::<f64>().ok(),
+                "position" => self.elapsed = val.parse::<f64>().ok(),
+                "artist" => self.artist = val.to_string(),
+                "album" => self.album = val.to_string(),
+                "title" => self.title = val.to_string(),
+                "date" => self.date = val.to_string(),
+                &_ => (),
+            }
         }
+
+        Ok(())
     }
 
-    pub fn get(&mut self) -> Result<()> {
-        let data = Command::new("mpc")
-            .arg("-f")
-            .arg("%artist% - (%date%) %album% - %title%a") // leave the extra "a" there
-            .output()
-            .context(Mpc)?;
-        self.data = String::from_utf8_lossy(&data.stdout)
-            .into_owned()
-            .split('\n')
-            .collect::<Vec<&str>>()[0]
-            .to_string();
-        self.data.pop();
+    // get the song from MOC, with the `Key: value` lines of `mocp -i`.
+    pub fn get_moc(&mut self) -> Result<()> {
+        let out = Command::new("mocp").arg("-i").output().context(Moc)?;
+        ensure!(
+            out.status.success(),
+            MocError {
+                message: String::from_utf8_lossy(&out.stderr).trim().to_string()
+            }
+        );
+
+        for line in String::from_utf8_lossy(&out.stdout).lines() {
+            let (key, val) = match line.split_once(": ") {
+                Some(kv) => kv,
+                None => continue,
+            };
+
+            match key {
+                "State" => {
+                    self.state = match val {
+                        "PLAY" => PlayState::Playing,
+                        "PAUSE" => PlayState::Paused,
+                        _ => PlayState::Stopped,
+                    }
+                }
+                "File" => self.file = val.to_string(),
+                "Artist" => self.artist = val.to_string(),
+                "Album" => self.album = val.to_string(),
+                "SongTitle" => self.title = val.to_string(),
+                "TotalSec" => self.duration = val.parse::<f64>().ok(),
+                "CurrentSec" => self.elapsed = val.parse::<f64>().ok(),
+                &_ => (),
+            }
+        }
 
         Ok(())
     }
 
-    // format it
-    pub fn format(&self) -> String {
-        self.data.clone()
+    // the song as "artist - (date) album - title", skipping what's missing.
+    fn song(&self) -> String {
+        if self.artist.is_empty() && self.title.is_empty() {
+            // untagged songs go by their file name
+            return match Path::new(&self.file).file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => "stopped".to_string(),
+            };
+        }
+
+        let mut album = self.album.clone();
+        if !self.date.is_empty() {
+            album = format!("({}) {}", self.date, album).trim_end().to_string();
+        }
+
+        [self.artist.as_str(), album.as_str(), self.title.as_str()]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .copied()
+            .collect::<Vec<&str>>()
+            .join(" - ")
+    }
+
+    // the value of a `%tag%`, or None for unknown tags.
+    fn tag(&self, tag: &str, bar: &Bar) -> Option<String> {
+        // a stopped player has no position worth showing
+        let (elapsed, duration) = match self.state {
+            PlayState::Stopped => (None, None),
+            _ => (self.elapsed, self.duration),
+        };
+
+        let value = match tag {
+            "artist" => self.artist.clone(),
+            "album" => self.album.clone(),
+            "title" => self.title.clone(),
+            "date" => self.date.clone(),
+            "file" => match Path::new(&self.file).file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => String::new(),
+            },
+            "song" => self.song(),
+            "state" => match self.state {
+                PlayState::Playing => "▶",
+                PlayState::Paused => "⏸",
+                PlayState::Stopped => "■",
+            }
+            .to_string(),
+            "status" => match self.state {
+                PlayState::Playing => "playing",
+                PlayState::Paused => "paused",
+                PlayState::Stopped => "stopped",
+            }
+            .to_string(),
+            "elapsed" => elapsed.map(time).unwrap_or_default(),
+            "duration" => duration.map(time).unwrap_or_default(),
+            "bar" => match (elapsed, duration) {
+                (Some(elapsed), Some(total)) if total > 0_f64 => bar.progress(elapsed / total * 100_f64),
+                _ => String::new(),
+            },
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    // format it, like mpc does: `%tag%`s are replaced with their values,
+    // and a `[...]` group is left out if any tag in it is empty.
+    pub fn format(&self, format: &str, bar: &Bar) -> String {
+        self.expand(&format.chars().collect::<Vec<char>>(), bar).0
+    }
+
+    // the expanded text, and whether all of its tags had a value.
+    fn expand(&self, format: &[char], bar: &Bar) -> (String, bool) {
+        let mut out = String::new();
+        let mut complete = true;
+        let mut i = 0;
+
+        while i < format.len() {
+            match format[i] {
+                '[' => match closing(&format[i..]) {
+                    Some(end) => {
+                        let (group, full) = self.expand(&format[i + 1..i + end], bar);
+                        if full {
+                            out.push_str(&group);
+                        }
+                        i += end + 1;
+                        continue;
+                    }
+                    None => out.push('['),
+                },
+                '%' => {
+                    let rest = &format[i + 1..];
+                    let tag = rest
+                        .iter()
+                        .position(|c| *c == '%')
+                        .map(|end| rest[..end].iter().collect::<String>());
+                    if let Some(tag) = tag {
+                        if let Some(value) = self.tag(&tag, bar) {
+                            complete &= !value.is_empty();
+                            out.push_str(&value);
+                            i += tag.chars().count() + 2;
+                            continue;
+                        }
+                    }
+                    out.push('%');
+                }
+                c => out.push(c),
+            }
+            i += 1;
+        }
+
+        (out, complete)
+    }
+}
+
+// the index of the `]` closing the group that starts the text.
+fn closing(format: &[char]) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in format.iter().enumerate() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+// seconds as `m:ss`, or `h:mm:ss` for an hour or more.
+fn time(seconds: f64) -> String {
+    let seconds = seconds.max(0_f64) as u64;
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::net::UnixListener;
+    use std::thread::{self, JoinHandle};
+
+    // a stand-in for MPD on a socket of its own: it sends the greeting,
+    // then checks that it gets each command in turn, and answers it.
+    fn fake_mpd(name: &str, greeting: &'static str, script: Vec<(&'static str, &'static str)>) -> (String, JoinHandle<()>) {
+        let path = env::temp_dir().join(format!("rsfetch-{}-{}.sock", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            stream.write_all(greeting.as_bytes()).unwrap();
+            for (command, answer) in script {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                assert_eq!(line.trim_end(), command);
+                stream.write_all(answer.as_bytes()).unwrap();
+            }
+        });
+
+        (path.to_string_lossy().into_owned(), server)
+    }
+
+    fn open(path: &str, password: Option<&str>) -> Result<Mpd> {
+        Mpd::open(Box::new(unix(path).unwrap()), password)
+    }
+
+    #[test]
+    fn mpd_greeting_and_answers() {
+        let (path, server) = fake_mpd(
+            "answers",
+            "OK MPD 0.23.5\n",
+            vec![("status", "volume: 50\nstate: play\nelapsed: 61.500\nOK\n")],
+        );
+
+        let mut mpd = open(&path, None).unwrap();
+        let status = mpd.command("status").unwrap();
+        assert_eq!(
+            status,
+            vec![
+                ("volume".to_string(), "50".to_string()),
+                ("state".to_string(), "play".to_string()),
+                ("elapsed".to_string(), "61.500".to_string()),
+            ]
+        );
+
+        server.join().unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn mpd_unexpected_greeting() {
+        let (path, server) = fake_mpd("greeting", "SSH-2.0-OpenSSH_9.6\n", vec![]);
+
+        let e = open(&path, None).err().unwrap();
+        assert_eq!(e.to_string(), "MPD returned an error: unexpected greeting \"SSH-2.0-OpenSSH_9.6\"");
+
+        server.join().unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn mpd_password() {
+        let (path, server) = fake_mpd(
+            "password",
+            "OK MPD 0.23.5\n",
+            vec![(r#"password "pa\"ss\\word""#, "OK\n"), ("currentsong", "OK\n")],
+        );
+
+        let mut mpd = open(&path, Some(r#"pa"ss\word"#)).unwrap();
+        assert!(mpd.command("currentsong").unwrap().is_empty());
+
+        server.join().unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn mpd_ack() {
+        let (path, server) = fake_mpd(
+            "ack",
+            "OK MPD 0.23.5\n",
+            vec![(r#"password "wrong""#, "ACK [3@0] {password} incorrect password\n")],
+        );
+
+        let e = open(&path, Some("wrong")).err().unwrap();
+        assert_eq!(e.to_string(), "MPD returned an error: [3@0] {password} incorrect password");
+
+        server.join().unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn mpd_connection_closed() {
+        // hanging up instead of answering
+        let (path, server) = fake_mpd("closed", "OK MPD 0.23.5\n", vec![("status", "")]);
+
+        let mut mpd = open(&path, None).unwrap();
+        let e = mpd.command("status").err().unwrap();
+        assert_eq!(e.to_string(), "MPD returned an error: connection closed");
+
+        server.join().unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+
+    fn song(state: PlayState) -> MusicInfo {
+        MusicInfo {
+            artist: "Queen".to_string(),
+            album: "A Night at the Opera".to_string(),
+            title: "Bohemian Rhapsody".to_string(),
+            date: "1975".to_string(),
+            file: "music/Queen/Bohemian Rhapsody.flac".to_string(),
+            elapsed: Some(61.5),
+            duration: Some(354_f64),
+            state,
+        }
+    }
+
+    #[test]
+    fn format_default() {
+        let bar = Bar::new(BarMode::Append, "ascii");
+        assert_eq!(
+            song(PlayState::Playing).format(DEFAULT_MUSIC_FORMAT, &bar),
+            "▶ Queen - (1975) A Night at the Opera - Bohemian Rhapsody (1:01/5:54)"
+        );
+        // a stopped player has no times, so their group goes
+        assert_eq!(
+            song(PlayState::Stopped).format(DEFAULT_MUSIC_FORMAT, &bar),
+            "■ Queen - (1975) A Night at the Opera - Bohemian Rhapsody"
+        );
+    }
+
+    #[test]
+    fn format_groups() {
+        let bar = Bar::new(BarMode::Append, "ascii");
+        let mut info = song(PlayState::Paused);
+        info.artist.clear();
+        info.duration = None;
+
+        assert_eq!(info.format("[%artist% - ]%title%", &bar), "Bohemian Rhapsody");
+        assert_eq!(info.format("%title%[ (%elapsed%[/%duration%])]", &bar), "Bohemian Rhapsody (1:01)");
+        assert_eq!(info.format("[%title% [%bar%]]", &bar), "Bohemian Rhapsody ");
+        assert_eq!(info.format("[[%artist%] %album%]", &bar), " A Night at the Opera");
+    }
+
+    #[test]
+    fn format_literals() {
+        let bar = Bar::new(BarMode::Append, "ascii");
+        let info = song(PlayState::Playing);
+
+        // unknown tags and unclosed groups are kept as they are
+        assert_eq!(info.format("%nope% 100%", &bar), "%nope% 100%");
+        assert_eq!(info.format("[%title%", &bar), "[Bohemian Rhapsody");
+        assert_eq!(info.format("%status%: %file%", &bar), "playing: Bohemian Rhapsody.flac");
+    }
+
+    #[test]
+    fn format_bar() {
+        let bar = Bar::new(BarMode::Append, "ascii");
+        let mut info = song(PlayState::Playing);
+        info.elapsed = Some(177_f64);
+
+        assert_eq!(info.format("%bar%", &bar), bar.progress(50_f64));
+        info.duration = Some(0_f64);
+        assert_eq!(info.format("[%bar%]", &bar), "");
+    }
+
+    // what `dbus-send --print-reply` prints for ListNames
+    const LIST_NAMES: &str = r#"method return time=1700000000.118022 sender=org.freedesktop.DBus -> destination=:1.130 serial=3 reply_serial=2
+   array [
+      string "org.freedesktop.DBus"
+      string ":1.7"
+      string "org.mpris.MediaPlayer2.spotify"
+      string "org.mpris.MediaPlayer2.firefox.instance_1_84"
+      string ":1.85"
+   ]
+"#;
+
+    // and for GetAll on Spotify's player
+    const GET_ALL: &str = r#"method return time=1700000000.124580 sender=:1.85 -> destination=:1.131 serial=1234 reply_serial=2
+   array [
+      dict entry(
+         string "PlaybackStatus"
+         variant             string "Playing"
+      )
+      dict entry(
+         string "Rate"
+         variant             double 1
+      )
+      dict entry(
+         string "Shuffle"
+         variant             boolean false
+      )
+      dict entry(
+         string "Metadata"
+         variant             array [
+               dict entry(
+                  string "mpris:trackid"
+                  variant                      object path "/com/spotify/track/7tFiyTwD0nx5a1eklYtX2J"
+               )
+               dict entry(
+                  string "mpris:length"
+                  variant                      uint64 354320000
+               )
+               dict entry(
+                  string "xesam:album"
+                  variant                      string "A Night at the "Opera""
+               )
+               dict entry(
+                  string "xesam:artist"
+                  variant                      array [
+                        string "Queen"
+                        string "Freddie Mercury"
+                     ]
+               )
+               dict entry(
+                  string "xesam:contentCreated"
+                  variant                      string "1975-11-21T00:00:00"
+               )
+               dict entry(
+                  string "xesam:title"
+                  variant                      string "Bohemian Rhapsody - Remastered 2011"
+               )
+            ]
+      )
+      dict entry(
+         string "Volume"
+         variant             double 0.75
+      )
+      dict entry(
+         string "Position"
+         variant             int64 61500000
+      )
+   ]
+"#;
+
+    #[test]
+    fn dbus_names() {
+        assert_eq!(
+            parse_names(LIST_NAMES),
+            vec![
+                "org.freedesktop.DBus",
+                ":1.7",
+                "org.mpris.MediaPlayer2.spotify",
+                "org.mpris.MediaPlayer2.firefox.instance_1_84",
+                ":1.85",
+            ]
+        );
+    }
+
+    #[test]
+    fn dbus_values() {
+        assert_eq!(dbus_value(r#"string "Roygbiv""#), "Roygbiv");
+        assert_eq!(dbus_value(r#"variant    string "say "hi"""#), r#"say "hi""#);
+        assert_eq!(dbus_value("variant             int64 151200000"), "151200000");
+        assert_eq!(dbus_value("variant double 1"), "1");
+        assert_eq!(dbus_value(r#"object path "/org/mpd/track/1""#), "/org/mpd/track/1");
+    }
+
+    #[test]
+    fn dbus_properties() {
+        let props = parse_properties(GET_ALL);
+        let get = |key: &str| props.get(key).cloned().unwrap_or_default();
+
+        assert_eq!(get("PlaybackStatus"), vec!["Playing"]);
+        assert_eq!(get("Position"), vec!["61500000"]);
+        assert_eq!(get("Volume"), vec!["0.75"]);
+        assert_eq!(get("Shuffle"), vec!["false"]);
+        // the Metadata dict is flattened into its keys
+        assert!(!props.contains_key("Metadata"));
+        assert_eq!(get("mpris:length"), vec!["354320000"]);
+        assert_eq!(get("mpris:trackid"), vec!["/com/spotify/track/7tFiyTwD0nx5a1eklYtX2J"]);
+        assert_eq!(get("xesam:artist"), vec!["Queen", "Freddie Mercury"]);
+        assert_eq!(get("xesam:album"), vec![r#"A Night at the "Opera""#]);
+        assert_eq!(get("xesam:contentCreated"), vec!["1975-11-21T00:00:00"]);
+        assert_eq!(get("xesam:title"), vec!["Bohemian Rhapsody - Remastered 2011"]);
+        assert_eq!(props.len(), 11);
+
+        assert!(parse_properties("method return time=1 sender=:1.85 -> destination=:1.9 serial=9 reply_serial=2\n   array [\n   ]\n").is_empty());
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(time(0_f64), "0:00");
+        assert_eq!(time(61.9), "1:01");
+        assert_eq!(time(3599_f64), "59:59");
+        assert_eq!(time(3600_f64), "1:00:00");
+        assert_eq!(time(36125_f64), "10:02:05");
+        assert_eq!(time(-5_f64), "0:00");
     }
 }
@@ -1,34 +1,129 @@
 use crate::*;
+use hyper::client::HttpConnector;
+use hyper_tls::HttpsConnector;
+use log::debug;
+use std::error::Error as _;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+pub const DEFAULT_IP_ENDPOINT: &str = "https://ipecho.net/plain";
+
+#[derive(PartialEq)]
+pub enum IpVersion {
+    // whichever the endpoint is reached over
+    Any,
+    V4,
+    V6,
+}
+
+impl IpVersion {
+    pub fn from(version: &str) -> IpVersion {
+        match version {
+            "4" => IpVersion::V4,
+            "6" => IpVersion::V6,
+            _ => IpVersion::Any,
+        }
+    }
+}
+
+pub struct NetworkOptions {
+    // a URL that answers with the public IP address as plain text,
+    // if one was given instead of the default
+    pub endpoint: Option<String>,
+    pub version: IpVersion,
+    pub timeout: Duration,
+}
 
 pub struct NetworkInfo {
     ip_address: String,
-    // TODO: implement
-    // interface: String,
-    // is_connected: bool,
-    // network_name: String,
-    // upload_speed: usize,
-    // download_speed: usize,
+    pub options: NetworkOptions,
 }
 
 impl NetworkInfo {
-    pub fn new() -> NetworkInfo {
+    pub fn new(options: NetworkOptions) -> NetworkInfo {
         NetworkInfo {
             ip_address: String::new(),
+            options,
         }
     }
 
+    // ask the endpoint for the public IP address. when it can't
+    // be reached in time, we're offline, which isn't an error,
+    // unless the endpoint we were told to use refuses to answer.
     pub async fn get(&mut self) -> Result<()> {
-        //let client = hyper::Client::new();
-	let https = hyper_tls::HttpsConnector::new();
-	let client = hyper::Client::builder()
-	    .build::<_, hyper::Body>(https);
-        let resp = client.get(hyper::Uri::from_static("https://ipecho.net/plain"))
-                    .await
-                    .context(Hyper)?;
-        let buf = hyper::body::to_bytes(resp)
-                    .await
-                    .context(Hyper)?;
-        self.ip_address = std::str::from_utf8(&buf).unwrap().to_string();
+        let endpoint = self.options.endpoint.as_deref().unwrap_or(DEFAULT_IP_ENDPOINT);
+        let uri = endpoint
+            .parse::<hyper::Uri>()
+            .ok()
+            .filter(|u| u.scheme_str() == Some("http") || u.scheme_str() == Some("https"))
+            .context(InvalidEndpoint {
+                endpoint: endpoint.to_string(),
+            })?;
+
+        // bound to a family's unspecified address, hyper
+        // only connects to the endpoint's addresses of that family
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+        match self.options.version {
+            IpVersion::V4 => http.set_local_address(Some(Ipv4Addr::UNSPECIFIED.into())),
+            IpVersion::V6 => http.set_local_address(Some(Ipv6Addr::UNSPECIFIED.into())),
+            IpVersion::Any => (),
+        }
+        let client = hyper::Client::builder().build::<_, hyper::Body>(HttpsConnector::new_with_connector(http));
+
+        let request = async {
+            let resp = client.get(uri).await?;
+            let status = resp.status();
+            let body = hyper::body::to_bytes(resp).await?;
+            Ok::<_, hyper::Error>((status, body))
+        };
+
+        let (status, body) = match tokio::time::timeout(self.options.timeout, request).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(e)) if !e.is_connect() => Err(e).context(Hyper)?,
+            Ok(Err(e)) if self.options.endpoint.is_some() && refused(&e) => Err(e).context(IpRefused {
+                endpoint: endpoint.to_string(),
+            })?,
+            Ok(Err(e)) => {
+                debug!("Unable to connect to {}, showing offline: {}", endpoint, e);
+                self.ip_address = "offline".to_string();
+                return Ok(());
+            }
+            Err(_) => {
+                debug!("No answer from {} in {:?}, showing offline.", endpoint, self.options.timeout);
+                self.ip_address = "offline".to_string();
+                return Ok(());
+            }
+        };
+
+        ensure!(
+            status.is_success(),
+            IpStatus {
+                status: status.to_string()
+            }
+        );
+
+        // the endpoint might as well be a captive portal's login page,
+        // so only an address of the right family will do. dual-stack
+        // servers tell IPv4 clients their IPv4-mapped IPv6 address.
+        let body = String::from_utf8_lossy(&body).trim().to_string();
+        let (expected, family): (&str, fn(&IpAddr) -> bool) = match self.options.version {
+            IpVersion::V4 => ("IPv4", IpAddr::is_ipv4),
+            IpVersion::V6 => ("IPv6", IpAddr::is_ipv6),
+            IpVersion::Any => ("IP", |_| true),
+        };
+        let ip = body
+            .parse::<IpAddr>()
+            .ok()
+            .map(|ip| ip.to_canonical())
+            .filter(family)
+            .context(InvalidIpResponse {
+                body: body.lines().next().unwrap_or_default().chars().take(40).collect::<String>(),
+                expected,
+            })?;
+
+        self.ip_address = ip.to_string();
         Ok(())
     }
 
@@ -37,3 +132,16 @@ impl NetworkInfo {
         self.ip_address.clone()
     }
 }
+
+// whether the connection was refused, which means the host is up, but
+// nothing listens on that port (e.g. a mistyped endpoint).
+fn refused(e: &hyper::Error) -> bool {
+    let mut source = e.source();
+    while let Some(e) = source {
+        if let Some(e) = e.downcast_ref::<io::Error>() {
+            return e.kind() == io::ErrorKind::ConnectionRefused;
+        }
+        source = e.source();
+    }
+    false
+}
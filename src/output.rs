@@ -1,4 +1,6 @@
+use crate::*;
 use std::vec::Vec;
+use unicode_width::UnicodeWidthChar;
 
 const E: char = 0x1B as char;
 
@@ -6,6 +8,21 @@ const E: char = 0x1B as char;
 struct KeyValue {
     key: String,
     val: String,
+    section: String,
+}
+
+// a line of output in the Rsfetch and Neofetch styles
+enum Row {
+    // the field's key (to look up its colors), the key as shown
+    // (if any) and the first line of its value
+    Field(String, Option<String>, String),
+    // a continuation line of the value above, with the field's key
+    More(String, String),
+    // the title of a section
+    Header(String),
+    Blank,
+    // printed as-is, e.g. the color palette
+    Raw(String),
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -15,22 +32,336 @@ pub enum OutputType {
     Neofetch,
 }
 
+// what to do with values too long for the terminal
+#[derive(PartialEq, Clone, Debug)]
+pub enum Overflow {
+    Truncate,
+    Wrap,
+    Ignore,
+}
+
+// where the logo goes, relative to the info
+#[derive(PartialEq, Clone, Debug)]
+pub enum LogoPosition {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl LogoPosition {
+    pub fn from(position: &str) -> LogoPosition {
+        match position {
+            "right" => LogoPosition::Right,
+            "top" => LogoPosition::Top,
+            "bottom" => LogoPosition::Bottom,
+            _ => LogoPosition::Left,
+        }
+    }
+}
+
+// the least room the info needs next to the logo;
+// on narrower terminals the logo is hidden instead.
+const MIN_INFO_WIDTH: usize = 30;
+
+// the glyphs that make up the borders of the Rsfetch style
+#[derive(Clone, Debug)]
+pub struct BorderStyle {
+    pub horizontal: char,
+    pub vertical: char,
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+    // where the rules of section headers meet the sides
+    pub left_tee: char,
+    pub right_tee: char,
+}
+
+// in the same order as `--border-chars`
+const BORDER_STYLES: &[(&str, &str)] = &[
+    ("default", "─│■■■■├┤"),
+    ("square", "─│┌┐└┘├┤"),
+    ("rounded", "─│╭╮╰╯├┤"),
+    ("double", "═║╔╗╚╝╠╣"),
+    ("heavy", "━┃┏┓┗┛┣┫"),
+    ("ascii", "-|++++++"),
+];
+
+impl BorderStyle {
+    // get one of the named border styles.
+    pub fn preset(name: &str) -> Result<BorderStyle> {
+        let (_, chars) = BORDER_STYLES
+            .iter()
+            .find(|(n, _)| n == &name)
+            .context(UnknownBorderStyle {
+                name: name.to_string(),
+            })?;
+
+        BorderStyle::custom(chars)
+    }
+
+    // make a border style from 8 glyphs: the horizontal and vertical
+    // edges, the top-left, top-right, bottom-left and bottom-right
+    // corners, and the left and right ends of section rules.
+    pub fn custom(chars: &str) -> Result<BorderStyle> {
+        let c = chars.chars().collect::<Vec<char>>();
+        ensure!(
+            c.len() == 8,
+            InvalidBorderChars {
+                chars: chars.to_string()
+            }
+        );
+
+        Ok(BorderStyle {
+            horizontal: c[0],
+            vertical: c[1],
+            top_left: c[2],
+            top_right: c[3],
+            bottom_left: c[4],
+            bottom_right: c[5],
+            left_tee: c[6],
+            right_tee: c[7],
+        })
+    }
+
+    // use the same glyph for all four corners (`--corners`).
+    pub fn set_corners(&mut self, corner: char) {
+        self.top_left = corner;
+        self.top_right = corner;
+        self.bottom_left = corner;
+        self.bottom_right = corner;
+    }
+}
+
+// the names of all the border styles.
+pub fn get_border_style_names() -> Vec<&'static str> {
+    BORDER_STYLES.iter().map(|(n, _)| *n).collect()
+}
+
+// the color blocks shown after the fields
+pub struct Palette {
+    // what the blocks are made of, or background-colored spaces if None
+    pub glyph: Option<String>,
+    // how many glyphs (or spaces) make up each block
+    pub width: usize,
+}
+
 pub struct OutputOptions {
     pub output_type: OutputType,
     pub caps: bool,
     pub bold: bool,
+    // whether to use colors at all
+    pub color: bool,
+    // whether stdout is a terminal, and so
+    // whether the cursor can be moved around
+    pub tty: bool,
+    // the width of the terminal, if known
+    pub term_width: Option<usize>,
+    pub overflow: Overflow,
+    // whether to group the fields into sections
+    pub sections: bool,
     pub use_borders: bool,
-    pub borders: char,
+    pub borders: BorderStyle,
+    // what goes between keys and values in the Rsfetch style
+    pub separator: String,
+    pub theme: Theme,
+    pub palette: Option<Palette>,
+    // where the logo goes, if not where the style puts it
+    pub logo_position: Option<LogoPosition>,
+    // the columns between the logo and the info, when next to each other
+    pub logo_gap: usize,
+    // whether to center a short logo vertically against the info
+    pub center_logo: bool,
 }
 
 pub struct OutputHelper {
     ascii: String,
+    ascii_colors: Vec<Color>,
     options: OutputOptions,
     data: Vec<KeyValue>,
+    section: String,
+    reserve: bool,
+}
+
+// the number of terminal columns the text takes up: escape
+// sequences take none (except for moving the cursor right),
+// and wide (e.g. CJK) characters take two.
+pub fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != E {
+            width += c.width().unwrap_or(0);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI: ESC [ <parameters> <final byte>
+            Some('[') => {
+                let mut params = String::new();
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        // moving the cursor right takes up columns too
+                        if c == 'C' {
+                            width += params.parse::<usize>().unwrap_or(1);
+                        }
+                        break;
+                    }
+                    params.push(c);
+                }
+            }
+            // OSC: ESC ] ... terminated by BEL or ESC \
+            // (and likewise DCS, e.g. Sixel, and APC, e.g. kitty images)
+            Some(']') | Some('P') | Some('_') => {
+                while let Some(c) = chars.next() {
+                    if c == '\u{7}' {
+                        break;
+                    }
+                    if c == E && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // anything else is a two-character sequence
+            _ => (),
+        }
+    }
+
+    width
+}
+
+// split the text into characters and escape sequences,
+// along with the display width of each.
+fn segments(text: &str) -> Vec<(String, usize)> {
+    let mut segments = Vec::new();
+    let mut escape = String::new();
+
+    for c in text.chars() {
+        if !escape.is_empty() {
+            escape.push(c);
+            // only CSI sequences end up in values
+            if (escape.len() > 2 && ('@'..='~').contains(&c)) || (escape.len() == 2 && c != '[') {
+                segments.push((escape.clone(), 0));
+                escape.clear();
+            }
+        } else if c == E {
+            escape.push(c);
+        } else {
+            segments.push((c.to_string(), c.width().unwrap_or(0)));
+        }
+    }
+
+    if !escape.is_empty() {
+        segments.push((escape, 0));
+    }
+
+    segments
+}
+
+// cut the text down to `width` columns, ending it with an ellipsis.
+// even without any room, the ellipsis stays, to show something was cut.
+pub fn truncate(text: &str, width: usize) -> String {
+    let width = width.max(1);
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+
+    let mut out = String::new();
+    let mut len = 0;
+    let mut escaped = false;
+    for (segment, w) in segments(text) {
+        if w == 0 {
+            escaped = escaped || segment.starts_with(E);
+        } else if len + w + 1 > width {
+            break;
+        }
+
+        len += w;
+        out.push_str(&segment);
+    }
+
+    out.push('…');
+    if escaped {
+        out.push_str(&format!("{}[0m", E));
+    }
+
+    out
+}
+
+// break the text into lines of at most `width` columns,
+// at spaces where possible.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut len = 0;
+
+    for word in text.split(' ') {
+        let w = display_width(word);
+        if len > 0 && len + 1 + w <= width {
+            line.push(' ');
+            line.push_str(word);
+            len += 1 + w;
+            continue;
+        }
+
+        if len > 0 {
+            lines.push(line.clone());
+            line.clear();
+            len = 0;
+        }
+
+        // words that don't fit on a line of their own are split up
+        for (segment, w) in segments(word) {
+            if len > 0 && len + w > width {
+                lines.push(line.clone());
+                line.clear();
+                len = 0;
+            }
+
+            line.push_str(&segment);
+            len += w;
+        }
+    }
+    lines.push(line);
+
+    lines
+}
+
+// pad the text with spaces to the given display width.
+pub fn pad(text: &str, width: usize) -> String {
+    let len = display_width(text);
+    if len < width {
+        format!("{}{}", text, " ".repeat(width - len))
+    } else {
+        text.to_string()
+    }
 }
 
-pub fn bold(text: &str) -> String {
-    format!("{0}[1m{1}{0}[0m", E, text)
+// wrap the text in the given color (and bold), if any.
+pub fn paint(text: &str, color: &Option<Color>, bold: bool) -> String {
+    let mut codes = String::new();
+    if bold {
+        codes.push_str(&format!("{}[1m", E));
+    }
+    if let Some(color) = color {
+        codes.push_str(&color.escape());
+    }
+
+    if codes.is_empty() {
+        return text.to_string();
+    }
+
+    // colored parts of the text (e.g. bars) reset the colors after
+    // themselves, so ours are set again after each of their resets
+    let mut text = text.to_string();
+    for reset in &[format!("{}[0m", E), format!("{}[39m", E)] {
+        text = text.replace(reset.as_str(), &format!("{}{}", reset, codes));
+    }
+    format!("{}{}{}[0m", codes, text, E)
 }
 
 impl OutputHelper {
@@ -38,163 +369,555 @@ impl OutputHelper {
     pub fn new(options: OutputOptions) -> OutputHelper {
         OutputHelper {
             ascii: String::new(),
+            ascii_colors: Vec::new(),
             options,
             data: Vec::new(),
+            section: String::new(),
+            reserve: false,
         }
     }
 
+    // put the fields added from now on in the named section
+    // (e.g. "Hardware"), if sections are turned on.
+    pub fn section(&mut self, name: &str) {
+        self.section = name.to_owned();
+    }
+
+    // values can span several lines (e.g. one per disk), and are
+    // shown as continuation rows under the same key.
     pub fn add(&mut self, key: &str, val: &str) {
         let item = KeyValue {
             key: key.to_owned(),
-            val: val.to_owned(),
+            val: val.trim_end_matches(['\n', '\r']).to_owned(),
+            section: self.section.clone(),
         };
 
         self.data.push(item);
     }
 
-    pub fn ascii(&mut self, ascii: String) {
+    pub fn ascii(&mut self, ascii: String, colors: Vec<Color>) {
         self.ascii = ascii;
+        self.ascii_colors = colors;
     }
 
-    pub fn output(&mut self) {
-        // minimal output style
-        if self.options.output_type == OutputType::Minimal {
-            for thing in self.data.clone() {
-                println!("{}", thing.val.replace("\n", ""));
+    // make room on the screen for the whole output before printing it,
+    // so that images drawn at the cursor don't scroll away from the text.
+    pub fn reserve_space(&mut self) {
+        self.reserve = true;
+    }
+
+    // split the logo into lines, replacing the `${cN}` placeholders
+    // with the matching color. every line starts with the color that
+    // was active at the end of the previous one and ends with a reset,
+    // so lines can be printed next to the info fields.
+    // returns the printable line and the line without any colors.
+    fn logo_lines(&self) -> Vec<(String, String)> {
+        let color = self.options.color;
+        let mut current: Option<&Color> = self.ascii_colors.first().filter(|_| color);
+        let mut lines = Vec::new();
+
+        for line in self.ascii.split('\n') {
+            let mut printed = String::new();
+            let mut plain = String::new();
+
+            if self.options.bold {
+                printed.push_str(&format!("{}[1m", E));
+            }
+            if let Some(color) = current {
+                printed.push_str(&color.escape());
             }
 
-            println!();
-        } else if self.options.output_type == OutputType::Rsfetch {
-            // Print logo
-            println!("{}", bold(&self.ascii));
-            let mut data = self.data.clone();
-
-            let mut max_len_key = 0;
-            let mut max_len_val = 0;
-            //Process data
-            for j in &mut data {
-                if self.options.bold {
-                    j.key = bold(&j.key);
-                }
+            let mut rest = line;
+            while let Some(start) = rest.find("${c") {
+                printed.push_str(&rest[..start]);
+                plain.push_str(&rest[..start]);
+                rest = &rest[start..];
 
-                if !self.options.caps {
-                    j.key = j.key.to_lowercase();
-                }
-                // Calculate length of key and value for padding
-                if j.key.len() > max_len_key {
-                    max_len_key = j.key.len();
-                }
-                // If not using borders, no need to calculate padding for `values`
-                if self.options.use_borders{
-                    if j.val.len() > max_len_val {
-                        max_len_val = j.val.len();
+                let end = match rest.find('}') {
+                    Some(end) => end,
+                    None => break,
+                };
+
+                match rest[3..end].parse::<usize>() {
+                    Ok(n) if n > 0 => {
+                        current = self.ascii_colors.get(n - 1).filter(|_| color);
+                        // some colors (e.g. ANSI_COLOR="0;34") reset bold
+                        if color {
+                            printed.push_str(&format!("{}[0m", E));
+                        }
+                        if self.options.bold {
+                            printed.push_str(&format!("{}[1m", E));
+                        }
+                        if let Some(color) = current {
+                            printed.push_str(&color.escape());
+                        }
+                        rest = &rest[end + 1..];
+                    }
+                    _ => {
+                        // not a placeholder, print it as-is
+                        printed.push_str("${");
+                        plain.push_str("${");
+                        rest = &rest[2..];
                     }
                 }
             }
-            // Set most options for borders
-            let mut border = "";
-            let mut width = 0;
-            if self.options.use_borders {
-                border = "│";
-
-                width = if self.options.bold {
-                    max_len_key + max_len_val + 2
-                } else {
-                    max_len_key + max_len_val + 10
-                };
-                // Top border
-                println!("{0}{1:─<2$}{0}", self.options.borders, "", width);
+            printed.push_str(rest);
+            plain.push_str(rest);
+            if color {
+                printed.push_str(&format!("{}[0m", E));
             }
 
-            // Print data content
-            for info in &data {
-                println!("{0} {1:<3$} = {5:2}{2:<4$} {0}", border, info.key, info.val, max_len_key + 2, max_len_val + 1, "");
-            }
-            // Bottom border
-            if self.options.use_borders {
-                println!("{0}{1:─<2$}{0}", self.options.borders, "", width);
-            } else {
-                println!();
-            }
-        } else if self.options.output_type == OutputType::Neofetch {
-            let mut width = 0;
-            let mut key_width = 0;
-            let ascii = self
-                .ascii
-                .clone()
-                .split('\n')
-                .map(|l| {
-                    if l.len() > width {
-                        width = l.len();
-                    }
+            lines.push((printed, plain));
+        }
 
-                    l.to_string()
-                })
-                .collect::<Vec<String>>();
+        lines
+    }
 
-            if !ascii.is_empty() {
-                width += 2;
-            }
+    // fit a value into `width` columns, by cutting it
+    // short or by spreading it over several lines.
+    fn fit(&self, val: &str, width: Option<usize>) -> Vec<String> {
+        match (width, &self.options.overflow) {
+            (Some(width), Overflow::Truncate) => vec![truncate(val, width)],
+            (Some(width), Overflow::Wrap) => wrap(val, width),
+            _ => vec![val.to_string()],
+        }
+    }
 
-            let stuff = self.data.clone();
+    // the width of the widest key.
+    fn key_width(&self) -> usize {
+        self.data
+            .iter()
+            .map(|i| display_width(&i.key))
+            .max()
+            .unwrap_or(0)
+    }
 
-            for i in &stuff {
-                let key = &i.key;
-                if key.len() > key_width {
-                    key_width = key.len();
+    // lay the fields out as rows, fitting values into `avail` columns
+    // (or `avail_keyless` for fields without a key). with sections on,
+    // fields are grouped under a header for each section, in the order
+    // the sections first show up, and with a blank row between them if `gap`.
+    fn rows(&self, avail: Option<usize>, avail_keyless: Option<usize>, gap: bool) -> Vec<Row> {
+        let mut sections: Vec<&str> = Vec::new();
+        if self.options.sections {
+            for i in &self.data {
+                if !sections.contains(&i.section.as_str()) {
+                    sections.push(&i.section);
                 }
             }
+        } else {
+            sections.push("");
+        }
 
-            key_width += 2;
+        let mut rows = Vec::new();
+        for section in sections {
+            if !section.is_empty() {
+                if gap && !rows.is_empty() {
+                    rows.push(Row::Blank);
+                }
 
-            let mut printed = 0;
-            for c in 0..stuff.len() {
-                let thing = stuff[c].clone();
-                let mut key = thing.key;
-                let val = thing.val;
+                if self.options.caps {
+                    rows.push(Row::Header(section.to_string()));
+                } else {
+                    rows.push(Row::Header(section.to_lowercase()));
+                }
+            }
 
+            let fields = self
+                .data
+                .iter()
+                .filter(|i| !self.options.sections || i.section == section);
+            for thing in fields {
+                let mut key = thing.key.clone();
                 if !self.options.caps {
                     key = key.to_lowercase();
                 }
 
-                // print logo
-                if c < ascii.len() {
-                    if self.options.bold {
-                        print!("{}{}[{}C", bold(&ascii[c]), E, (width - ascii[c].len()));
+                let avail = if key.is_empty() { avail_keyless } else { avail };
+                let lines = thing.val.split('\n').flat_map(|l| self.fit(l, avail));
+                for (i, val) in lines.enumerate() {
+                    if key.is_empty() {
+                        rows.push(Row::Field(thing.key.clone(), None, val));
+                    } else if i == 0 {
+                        rows.push(Row::Field(thing.key.clone(), Some(key.clone()), val));
                     } else {
-                        print!("{}{}[{}C", ascii[c], E, (width - ascii[c].len()));
+                        rows.push(Row::More(thing.key.clone(), val));
                     }
-                } else {
-                    print!("{}[{}C", E, width);
                 }
+            }
+        }
+
+        rows
+    }
+
+    // the 8 normal and the 8 bright colors, as two lines of blocks.
+    // without colors there's nothing to show.
+    fn palette_lines(&self) -> Vec<String> {
+        let palette = match &self.options.palette {
+            Some(p) if self.options.color => p,
+            _ => return Vec::new(),
+        };
+
+        let mut lines = Vec::new();
+        for colors in &[0..8, 8..16] {
+            let mut line = String::new();
+            for n in colors.clone() {
+                let color = Color::Ansi(n);
+                let block = match &palette.glyph {
+                    Some(glyph) => format!("{}{}", color.escape(), glyph.repeat(palette.width)),
+                    None => format!("{}{}", color.background(), " ".repeat(palette.width)),
+                };
+                line.push_str(&block);
+            }
+            line.push_str(&format!("{}[0m", E));
+            lines.push(line);
+        }
+
+        lines
+    }
+
+    // move the cursor `n` columns to the right, or pad
+    // with spaces when we aren't printing to a terminal.
+    fn skip(&self, n: usize) -> String {
+        if n == 0 {
+            String::new()
+        } else if self.options.tty {
+            format!("{}[{}C", E, n)
+        } else {
+            " ".repeat(n)
+        }
+    }
+
+    // the Rsfetch style: a table of keys and values, optionally with borders.
+    fn table(&self, term_width: Option<usize>) -> Vec<String> {
+        let mut lines = Vec::new();
+        let theme = &self.options.theme;
+        let borders = self.options.use_borders;
+        let glyphs = &self.options.borders;
+
+        // Calculate length of key for padding
+        let max_len_key = self.key_width();
+
+        // Everything in a row but the key and value: the spaces
+        // around them, and the separator between them.
+        let sep_width = display_width(&self.options.separator);
+        let extra = sep_width + 7;
+
+        // Fit the values into what's left of the terminal once the
+        // keys, separators and borders are accounted for.
+        let taken = max_len_key + extra + if borders { 2 } else { 0 };
+        let avail = term_width.map(|w| w.saturating_sub(taken));
+        let rows = self.rows(avail, avail, !borders);
+
+        // If not using borders, no need to calculate padding for `values`
+        let mut max_len_val = 0;
+        if borders {
+            for row in &rows {
+                max_len_val = max_len_val.max(match row {
+                    Row::Field(_, _, val) | Row::More(_, val) => display_width(val),
+                    // make room for the section title in the rule
+                    Row::Header(title) => (display_width(title) + 4).saturating_sub(max_len_key + extra),
+                    Row::Blank | Row::Raw(_) => 0,
+                });
+            }
+        }
+        let inner = max_len_key + max_len_val + extra;
 
-                // print key and value
-                if key != "" {
-                    if self.options.bold {
-                        print!("{}{}[{}C{}\n", bold(&key), E, (key_width - key.len()), val);
+        // Set most options for borders
+        let mut border = String::new();
+        let line = glyphs.horizontal.to_string().repeat(inner);
+        let corner = |c: char| paint(&c.to_string(), &theme.corner, false);
+        if borders {
+            border = paint(&glyphs.vertical.to_string(), &theme.border, false);
+            // Top border
+            lines.push(format!(
+                "{}{}{}",
+                corner(glyphs.top_left),
+                paint(&line, &theme.border, false),
+                corner(glyphs.top_right)
+            ));
+        }
+
+        // Data content
+        // (padding is done before painting, so escape codes don't count)
+        let separator = paint(&self.options.separator, &theme.separator, false);
+        let no_separator = " ".repeat(sep_width);
+        for row in &rows {
+            let (field, key, sep, val) = match row {
+                Row::Field(field, key, val) => (field, key.clone().unwrap_or_default(), separator.as_str(), val),
+                Row::More(field, val) => (field, String::new(), no_separator.as_str(), val),
+                Row::Header(title) => {
+                    if borders {
+                        // a rule across the table, with the section title in it
+                        let rest = inner.saturating_sub(display_width(title) + 3);
+                        lines.push(format!(
+                            "{}{}{}",
+                            paint(&format!("{}{} ", glyphs.left_tee, glyphs.horizontal), &theme.border, false),
+                            paint(title, &theme.key, self.options.bold),
+                            paint(
+                                &format!(" {}{}", glyphs.horizontal.to_string().repeat(rest), glyphs.right_tee),
+                                &theme.border,
+                                false
+                            )
+                        ));
                     } else {
-                        print!("{}{}[{}C{}\n", key, E, (key_width - key.len()), val);
+                        lines.push(format!(" {}", paint(title, &theme.key, self.options.bold)));
                     }
-                } else if self.options.bold {
-                    print!("{}\n", bold(&val));
-                } else {
-                    print!("{}\n", val);
+                    continue;
+                }
+                Row::Blank => {
+                    lines.push(String::new());
+                    continue;
                 }
+                Row::Raw(line) => {
+                    lines.push(line.clone());
+                    continue;
+                }
+            };
 
-                printed = c;
+            let (key_color, val_color) = theme.field(field);
+            let key = pad(&key, max_len_key + 2);
+            let val = pad(val, max_len_val + 1);
+            lines.push(format!(
+                "{0} {1}{2}  {3} {0}",
+                border,
+                paint(&key, &key_color, self.options.bold),
+                sep,
+                paint(&val, &val_color, false)
+            ));
+        }
+        // Bottom border
+        if borders {
+            lines.push(format!(
+                "{}{}{}",
+                corner(glyphs.bottom_left),
+                paint(&line, &theme.border, false),
+                corner(glyphs.bottom_right)
+            ));
+        }
+
+        // Color palette, below the table
+        let palette = self.palette_lines();
+        if !borders && !palette.is_empty() {
+            lines.push(String::new());
+        }
+        for line in palette {
+            if borders {
+                lines.push(line);
+            } else {
+                lines.push(format!(" {}", line));
             }
+        }
+        if !borders {
+            lines.push(String::new());
+        }
 
-            if ascii.len() > printed {
-                for i in (printed + 1)..ascii.len() {
-                    if self.options.bold {
-                        print!("{}\n", bold(&ascii[i]));
-                    } else {
-                        print!("{}\n", ascii[i]);
-                    }
+        lines
+    }
+
+    // the Neofetch style: a column of keys and a column of values.
+    fn columns(&self, term_width: Option<usize>) -> Vec<String> {
+        let key_width = self.key_width() + 2;
+
+        // Lay out the rows first, fitting the values into what's left
+        // of the terminal next to the keys. Fields without a key
+        // (e.g. user@hostname) take up the key column too.
+        let mut rows = self.rows(
+            term_width.map(|w| w.saturating_sub(key_width)),
+            term_width,
+            true,
+        );
+
+        // Color palette, below the fields
+        let palette = self.palette_lines();
+        if !palette.is_empty() {
+            rows.push(Row::Blank);
+            rows.extend(palette.into_iter().map(Row::Raw));
+        }
+
+        let theme = &self.options.theme;
+        rows.iter()
+            .map(|row| match row {
+                Row::Field(field, Some(key), val) => {
+                    let (key_color, val_color) = theme.field(field);
+                    format!(
+                        "{}{}{}",
+                        paint(key, &key_color, self.options.bold),
+                        self.skip(key_width - display_width(key)),
+                        paint(val, &val_color, false)
+                    )
+                }
+                Row::Field(field, None, val) => paint(val, &theme.field(field).1, self.options.bold),
+                Row::More(field, val) => format!("{}{}", self.skip(key_width), paint(val, &theme.field(field).1, false)),
+                Row::Header(title) => paint(title, &theme.key, self.options.bold),
+                Row::Blank => String::new(),
+                Row::Raw(line) => line.clone(),
+            })
+            .collect()
+    }
+
+    // put the logo and the info next to each other, with the
+    // logo `logo_width` columns wide and on the left if `left`.
+    fn beside(&self, logo: &[(String, String)], logo_width: usize, info: &[String], left: bool) -> Vec<String> {
+        let gap = self.options.logo_gap;
+
+        // a logo shorter than the info can be moved down to its middle
+        let offset = if self.options.center_logo {
+            info.len().saturating_sub(logo.len()) / 2
+        } else {
+            0
+        };
+        let height = info.len().max(logo.len() + offset);
+        let info_width = info.iter().map(|l| display_width(l)).max().unwrap_or(0);
+
+        (0..height)
+            .map(|i| {
+                let logo_line = i.checked_sub(offset).and_then(|i| logo.get(i));
+                let info_line = info.get(i).map(String::as_str).unwrap_or("");
+
+                match (logo_line, left) {
+                    (Some((line, plain)), true) if i < info.len() => format!(
+                        "{}{}{}",
+                        line,
+                        self.skip(logo_width + gap - display_width(plain)),
+                        info_line
+                    ),
+                    (Some((line, _)), true) => line.clone(),
+                    (None, true) => format!("{}{}", self.skip(logo_width + gap), info_line),
+                    (Some((line, _)), false) => format!(
+                        "{}{}{}",
+                        info_line,
+                        self.skip(info_width + gap - display_width(info_line)),
+                        line
+                    ),
+                    (None, false) => info_line.to_string(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn output(&mut self) {
+        // minimal output style
+        if self.options.output_type == OutputType::Minimal {
+            for thing in &self.data {
+                for line in thing.val.split('\n') {
+                    println!("{}", line);
                 }
             }
 
-            print!("\n"); // newline
+            println!();
+            return;
         }
+
+        let info = |width: Option<usize>| {
+            if self.options.output_type == OutputType::Rsfetch {
+                self.table(width)
+            } else {
+                self.columns(width)
+            }
+        };
+
+        // the Rsfetch style puts the logo on top, the Neofetch style on the left
+        let position = match &self.options.logo_position {
+            Some(p) => p.clone(),
+            None if self.options.output_type == OutputType::Rsfetch => LogoPosition::Top,
+            None => LogoPosition::Left,
+        };
+        let beside = position == LogoPosition::Left || position == LogoPosition::Right;
+
+        let mut logo = self.logo_lines();
+        let logo_width = logo.iter().map(|(_, plain)| display_width(plain)).max().unwrap_or(0);
+
+        // Hide the logo when the terminal is too narrow for it, or for
+        // it and (at least part of) the info next to it.
+        if let Some(term_width) = self.options.term_width {
+            let needed = if beside {
+                let info_width = info(None).iter().map(|l| display_width(l)).max().unwrap_or(0);
+                logo_width + self.options.logo_gap + info_width.min(MIN_INFO_WIDTH)
+            } else {
+                logo_width
+            };
+
+            if term_width < needed {
+                logo.clear();
+            }
+        }
+
+        let mut lines = if logo.is_empty() {
+            info(self.options.term_width)
+        } else if beside {
+            let taken = logo_width + self.options.logo_gap;
+            let info = info(self.options.term_width.map(|w| w.saturating_sub(taken)));
+            self.beside(&logo, logo_width, &info, position == LogoPosition::Left)
+        } else {
+            let mut info = info(self.options.term_width);
+            let logo = logo.into_iter().map(|(line, _)| line);
+            if position == LogoPosition::Top {
+                logo.chain(info).collect()
+            } else {
+                info.extend(logo);
+                info
+            }
+        };
+
+        if self.options.output_type == OutputType::Neofetch {
+            lines.push(String::new()); // newline
+        }
+
+        // scroll down and come back up, so the screen has room for it all
+        if self.reserve && self.options.tty && !lines.is_empty() {
+            print!("{}{}[{}A", "\n".repeat(lines.len()), E, lines.len());
+        }
+
+        for line in lines {
+            println!("{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widths() {
+        assert_eq!(display_width(""), 0);
+        assert_eq!(display_width("rsfetch"), 7);
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("café ▶ …"), 8);
+        // colors take no room, moving the cursor right does
+        assert_eq!(display_width("\x1B[1m\x1B[38;2;1;2;3mred\x1B[0m"), 3);
+        assert_eq!(display_width("a\x1B[5Cb"), 7);
+        assert_eq!(display_width("a\x1B[Cb"), 3);
+        // OSC links, ended by BEL or ST
+        assert_eq!(display_width("\x1B]8;;https://example.com\x07link\x1B]8;;\x07"), 4);
+        assert_eq!(display_width("\x1B]8;;https://example.com\x1B\\link\x1B]8;;\x1B\\"), 4);
+    }
+
+    #[test]
+    fn truncating() {
+        assert_eq!(truncate("hello world", 11), "hello world");
+        assert_eq!(truncate("hello world", 5), "hell…");
+        assert_eq!(truncate("日本語", 4), "日…");
+        // the ellipsis stays, even without room for it
+        assert_eq!(truncate("hello", 1), "…");
+        assert_eq!(truncate("hello", 0), "…");
+        assert_eq!(truncate("", 0), "");
+        // colors are kept, and reset after the cut
+        assert_eq!(truncate("\x1B[31mred text\x1B[0m", 4), "\x1B[31mred…\x1B[0m");
+    }
+
+    #[test]
+    fn wrapping() {
+        assert_eq!(wrap("the quick brown fox", 9), vec!["the quick", "brown fox"]);
+        assert_eq!(wrap("the quick brown fox", 19), vec!["the quick brown fox"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(
+            wrap("a supercalifragilistic word", 8),
+            vec!["a", "supercal", "ifragili", "stic", "word"]
+        );
+        assert_eq!(wrap("日本語", 3), vec!["日", "本", "語"]);
+        assert_eq!(wrap("日", 1), vec!["日"]);
+        assert_eq!(wrap("", 5), vec![""]);
+        assert_eq!(wrap("\x1B[32mok\x1B[0m fine", 2), vec!["\x1B[32mok\x1B[0m", "fi", "ne"]);
     }
 }
@@ -0,0 +1,366 @@
+// PNG and JPEG images as logos, drawn with Unicode half blocks
+// (two truecolor pixels per cell) or braille dots (eight per cell),
+// or shown as real images with the kitty graphics protocol or Sixel.
+use crate::*;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageOutputFormat, Rgba, RgbaImage};
+use std::collections::HashMap;
+use std::env;
+use std::io::Cursor;
+use std::path::Path;
+
+const E: char = 0x1B as char;
+
+// the bit of each of the 8 dots of a braille cell, by x and y
+const DOTS: [(u32, u32, u32); 8] = [
+    (0, 0, 0x01),
+    (0, 1, 0x02),
+    (0, 2, 0x04),
+    (1, 0, 0x08),
+    (1, 1, 0x10),
+    (1, 2, 0x20),
+    (0, 3, 0x40),
+    (1, 3, 0x80),
+];
+
+pub enum ImageStyle {
+    // `▀` with the top pixel in the foreground and the bottom one in the background
+    Blocks,
+    // a braille character per 2x4 pixels, which also works without colors
+    Braille,
+}
+
+impl ImageStyle {
+    pub fn from(style: &str) -> ImageStyle {
+        match style {
+            "braille" => ImageStyle::Braille,
+            _ => ImageStyle::Blocks,
+        }
+    }
+}
+
+// how images get to the terminal
+#[derive(PartialEq)]
+pub enum ImageProtocol {
+    // drawn with text, as per `ImageStyle`
+    Text,
+    Kitty,
+    Sixel,
+}
+
+impl ImageProtocol {
+    // `auto` picks the protocol the terminal speaks, if `graphics`
+    // (i.e. we are printing to a terminal, with colors).
+    pub fn from(protocol: &str, graphics: bool) -> ImageProtocol {
+        match protocol {
+            "kitty" => ImageProtocol::Kitty,
+            "sixel" => ImageProtocol::Sixel,
+            "auto" if graphics => ImageProtocol::detect(),
+            _ => ImageProtocol::Text,
+        }
+    }
+
+    // guess the protocol from TERM and TERM_PROGRAM.
+    fn detect() -> ImageProtocol {
+        let term = env::var("TERM").unwrap_or_default();
+        let program = env::var("TERM_PROGRAM").unwrap_or_default();
+
+        if term.contains("kitty") || term.contains("ghostty") || ["WezTerm", "ghostty"].contains(&program.as_str()) {
+            ImageProtocol::Kitty
+        } else if term.starts_with("foot")
+            || term.contains("mlterm")
+            || term.contains("contour")
+            || ["mintty", "iTerm.app"].contains(&program.as_str())
+        {
+            ImageProtocol::Sixel
+        } else {
+            ImageProtocol::Text
+        }
+    }
+}
+
+// whether the file is an image, going by its extension.
+pub fn is_image(path: &str) -> bool {
+    match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some(ext) => ["png", "jpg", "jpeg"].contains(&ext.to_lowercase().as_str()),
+        None => false,
+    }
+}
+
+// draw the image `columns` cells wide, as logo text. without colors
+// only braille makes sense, so that is used whatever the style.
+// with a graphics protocol, the image is drawn over blank lines
+// that take up as many cells as it does.
+pub fn render_image(
+    path: &str,
+    columns: u32,
+    style: &ImageStyle,
+    protocol: &ImageProtocol,
+    color: bool,
+) -> Result<String> {
+    let img = image::open(path).context(ReadImage)?;
+
+    // terminals that don't tell are assumed to have cells twice as tall as wide
+    let (cell_width, cell_height) = cell_size().unwrap_or((10, 20));
+    let width = columns * cell_width;
+    let height = img.height() * width / img.width().max(1);
+    let rows = height.div_ceil(cell_height).max(1);
+
+    let graphic = match protocol {
+        ImageProtocol::Text => {
+            return match style {
+                ImageStyle::Blocks if color => Ok(blocks(&scale(&img, columns, 2))),
+                _ => Ok(braille(&scale(&img, columns * 2, 4), color)),
+            };
+        }
+        // the terminal scales the image to the cells, but there's
+        // no need to send it more pixels than they have
+        ImageProtocol::Kitty if img.width() > width => kitty(&img.thumbnail(width, height), columns, rows)?,
+        ImageProtocol::Kitty => kitty(&img, columns, rows)?,
+        ImageProtocol::Sixel => sixel(&img.resize_exact(width, height, FilterType::Triangle).to_rgba8()),
+    };
+
+    // save the cursor, draw the image, and go back to where it was
+    let mut lines = vec![format!("{}7{}{}8{}", E, graphic, E, " ".repeat(columns as usize))];
+    for _ in 1..rows {
+        lines.push(" ".repeat(columns as usize));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+fn base64(data: &[u8]) -> String {
+    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0_u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(CHARS[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
+// the image as PNG, sent in chunks, to be shown over `columns` x `rows`
+// cells without moving the cursor.
+fn kitty(img: &DynamicImage, columns: u32, rows: u32) -> Result<String> {
+    let mut png = Vec::new();
+    img.write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .context(EncodeImage)?;
+
+    let data = base64(&png);
+    let chunks = data.as_bytes().chunks(4096).collect::<Vec<&[u8]>>();
+    let mut out = String::new();
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        if i == 0 {
+            out.push_str(&format!("{}_Ga=T,f=100,q=2,C=1,c={},r={},m={};", E, columns, rows, more));
+        } else {
+            out.push_str(&format!("{}_Gm={};", E, more));
+        }
+        out.push_str(&String::from_utf8_lossy(chunk));
+        out.push_str(&format!("{}\\", E));
+    }
+
+    Ok(out)
+}
+
+// the image in Sixel, with its colors reduced to a 6x6x6 color cube.
+// transparent pixels are left alone.
+fn sixel(img: &RgbaImage) -> String {
+    let (width, height) = img.dimensions();
+    let index = |p: &Rgba<u8>| {
+        let level = |c: u8| (c as u32 * 5 + 127) / 255;
+        (level(p[0]) * 36 + level(p[1]) * 6 + level(p[2])) as usize
+    };
+
+    let mut out = format!("{}P0;1;0q\"1;1;{};{}", E, width, height);
+
+    // the palette, in percentages
+    let mut used = img.pixels().filter(|p| opaque(p)).map(index).collect::<Vec<usize>>();
+    used.sort_unstable();
+    used.dedup();
+    for i in &used {
+        let percent = |level: usize| level * 100 / 5;
+        out.push_str(&format!("#{};2;{};{};{}", i, percent(i / 36), percent(i / 6 % 6), percent(i % 6)));
+    }
+
+    // six rows of pixels at a time, with a pass over them for each color
+    for band in (0..height).step_by(6) {
+        let mut passes: HashMap<usize, Vec<u8>> = HashMap::new();
+        for x in 0..width {
+            for dy in 0..6.min(height - band) {
+                let pixel = img.get_pixel(x, band + dy);
+                if opaque(pixel) {
+                    let pass = passes.entry(index(pixel)).or_insert_with(|| vec![0; width as usize]);
+                    pass[x as usize] |= 1 << dy;
+                }
+            }
+        }
+
+        let mut colors = passes.keys().copied().collect::<Vec<usize>>();
+        colors.sort_unstable();
+        for color in colors {
+            out.push_str(&format!("#{}", color));
+
+            // runs of the same sixel are repeated with `!`
+            let pass = &passes[&color];
+            let mut x = 0;
+            while x < pass.len() {
+                let run = pass[x..].iter().take_while(|s| **s == pass[x]).count();
+                let sixel = (pass[x] + 63) as char;
+                if run > 3 {
+                    out.push_str(&format!("!{}{}", run, sixel));
+                } else {
+                    out.push_str(&sixel.to_string().repeat(run));
+                }
+                x += run;
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+
+    out.push_str(&format!("{}\\", E));
+    out
+}
+
+// scale the image to `width` pixels, keeping its aspect ratio, with
+// the height rounded up to a multiple of the pixels in a cell's height.
+// (cells are about twice as tall as they are wide, so both half blocks
+// and braille dots come out about square.)
+fn scale(img: &DynamicImage, width: u32, cell_height: u32) -> RgbaImage {
+    let (w, h) = img.dimensions();
+    let width = width.max(1);
+    let height = (h as f64 * width as f64 / w.max(1) as f64).round() as u32;
+    let height = height.div_ceil(cell_height).max(1) * cell_height;
+
+    img.resize_exact(width, height, FilterType::Triangle).to_rgba8()
+}
+
+fn opaque(pixel: &Rgba<u8>) -> bool {
+    pixel[3] >= 128
+}
+
+fn rgb(pixel: &Rgba<u8>) -> Color {
+    Color::Rgb(pixel[0], pixel[1], pixel[2])
+}
+
+fn blocks(img: &RgbaImage) -> String {
+    let (width, height) = img.dimensions();
+    let mut lines = Vec::new();
+
+    for y in (0..height).step_by(2) {
+        let mut line = String::new();
+        for x in 0..width {
+            let top = img.get_pixel(x, y);
+            let bottom = img.get_pixel(x, y + 1);
+
+            // transparent pixels are left to the terminal's background
+            line.push_str(&format!("{}[0m", E));
+            match (opaque(top), opaque(bottom)) {
+                (true, true) => line.push_str(&format!("{}{}▀", rgb(top).escape(), rgb(bottom).background())),
+                (true, false) => line.push_str(&format!("{}▀", rgb(top).escape())),
+                (false, true) => line.push_str(&format!("{}▄", rgb(bottom).escape())),
+                (false, false) => line.push(' '),
+            }
+        }
+        line.push_str(&format!("{}[0m", E));
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+fn braille(img: &RgbaImage, color: bool) -> String {
+    let (width, height) = img.dimensions();
+    let luma = |p: &Rgba<u8>| 0.299 * p[0] as f64 + 0.587 * p[1] as f64 + 0.114 * p[2] as f64;
+
+    // dots are lit where the image isn't transparent, or for
+    // images without transparency, where it is brighter than on average
+    let opaque_pixels = img.pixels().filter(|p| opaque(p)).collect::<Vec<&Rgba<u8>>>();
+    let mean = if opaque_pixels.len() < (width * height) as usize {
+        0_f64
+    } else {
+        opaque_pixels.iter().map(|p| luma(p)).sum::<f64>() / opaque_pixels.len().max(1) as f64
+    };
+
+    let mut lines = Vec::new();
+    for y in (0..height).step_by(4) {
+        let mut line = String::new();
+        for x in (0..width).step_by(2) {
+            let mut bits = 0;
+            // the sum of the lit dots' colors, and their number
+            let mut sum = [0_u32; 3];
+            let mut lit = 0;
+
+            for (dx, dy, bit) in DOTS.iter() {
+                // an odd width leaves the last column of dots without pixels
+                let pixel = match img.get_pixel_checked(x + dx, y + dy) {
+                    Some(p) => p,
+                    None => continue,
+                };
+                if opaque(pixel) && luma(pixel) >= mean {
+                    bits |= bit;
+                    sum.iter_mut().zip(pixel.0.iter()).for_each(|(s, c)| *s += *c as u32);
+                    lit += 1;
+                }
+            }
+
+            if bits == 0 {
+                line.push(' ');
+                continue;
+            }
+
+            if color {
+                line.push_str(&Color::Rgb((sum[0] / lit) as u8, (sum[1] / lit) as u8, (sum[2] / lit) as u8).escape());
+            }
+            line.push(std::char::from_u32(0x2800 + bits).unwrap_or(' '));
+        }
+        if color {
+            line.push_str(&format!("{}[0m", E));
+        }
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn white(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255])))
+    }
+
+    #[test]
+    fn scaling() {
+        assert_eq!(scale(&white(2, 8), 1, 4).dimensions(), (1, 4));
+        assert_eq!(scale(&white(2, 8), 0, 4).dimensions(), (1, 4));
+        // rounded up to whole cells
+        assert_eq!(scale(&white(10, 10), 4, 4).dimensions(), (4, 4));
+        assert_eq!(scale(&white(10, 30), 4, 4).dimensions(), (4, 12));
+    }
+
+    #[test]
+    fn braille_one_pixel_wide() {
+        // only the left column of dots has pixels
+        assert_eq!(braille(&scale(&white(2, 8), 1, 4), false), "⡇");
+        assert_eq!(braille(&scale(&white(2, 8), 2, 4), false), "⣿\n⣿");
+    }
+
+    #[test]
+    fn blocks_one_pixel_wide() {
+        let lines = blocks(&scale(&white(2, 8), 1, 2));
+        assert_eq!(lines.lines().count(), 2);
+        assert!(lines.lines().all(|l| display_width(l) == 1 && l.contains('▀')));
+    }
+}
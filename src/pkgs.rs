@@ -9,7 +9,7 @@ pub enum PkgManager {
     Debian,
     Void,
     Fedora,
-    BSD,
+    Bsd,
     Solus,
     Suse,
     Alpine,
@@ -60,7 +60,7 @@ impl PkgInfo {
 					.arg("installed")
                     .output()
                     .context(Pkgcount)?,
-                PkgManager::BSD => Command::new("pkg")
+                PkgManager::Bsd => Command::new("pkg")
 					.arg("info")
 					.output()
 					.context(Pkgcount)?,
@@ -116,7 +116,7 @@ impl PkgInfo {
             "xbps" => PkgManager::Void,
             "xbps-query" => PkgManager::Void,
             "dnf" => PkgManager::Fedora,
-            "pkg" => PkgManager::BSD,
+            "pkg" => PkgManager::Bsd,
             "eopkg" => PkgManager::Solus,
             "rpm" => PkgManager::Suse,
             "apk" => PkgManager::Alpine,
@@ -132,7 +132,7 @@ impl PkgInfo {
     // format it
     pub fn format(&self) -> String {
         if self.count > 0 {
-            return format!("{}", self.count);
+            format!("{}", self.count)
         } else {
             "nah!".to_owned()
         }
@@ -6,12 +6,12 @@ use std::process;
 use std::vec::Vec;
 
 fn get_ppid(id: u32) -> Option<u32> {
-    if fs::metadata(&format!("/proc/{}/status", id)).is_err() {
+    if fs::metadata(format!("/proc/{}/status", id)).is_err() {
         return None;
     }
 
     let mut ppid_str = String::new();
-    fs::read_to_string(&format!("/proc/{}/status", id))
+    fs::read_to_string(format!("/proc/{}/status", id))
         .unwrap()
         .split('\n')
         .for_each(|i| {
@@ -63,7 +63,7 @@ impl Terminal {
             // the process at one go, instead of reading
             // the process-info file TWICE
             let mut ppname = String::new();
-            fs::read_to_string(&format!("/proc/{}/status", lastid))
+            fs::read_to_string(format!("/proc/{}/status", lastid))
                 .unwrap()
                 .split('\n')
                 .for_each(|i| {
@@ -130,7 +130,7 @@ impl Terminal {
                 self.name = "urxvt".to_string();
                 break;
             } else {
-                self.name = ppname.split('/').last().unwrap().to_string();
+                self.name = ppname.split('/').next_back().unwrap().to_string();
                 break;
             }
         }
@@ -138,7 +138,7 @@ impl Terminal {
         // clear terminal name if it's empty
         // or a non-terminal field
         let ttye = self.name.clone();
-        if ttye == "" || ttye == "systemd" {
+        if ttye.is_empty() || ttye == "systemd" {
             self.name = "?".to_string();
         }
 
@@ -0,0 +1,176 @@
+// how fast the interfaces are receiving and sending, measured by
+// sampling their byte counters in /proc/net/dev twice.
+use crate::*;
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub struct Throughput {
+    pub interface: String,
+    // in bytes per second
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    // in bytes, since boot (or since the interface came up)
+    pub rx_total: u64,
+    pub tx_total: u64,
+}
+
+pub struct ThroughputInfo {
+    pub interfaces: Vec<Throughput>,
+}
+
+impl ThroughputInfo {
+    pub fn new() -> ThroughputInfo {
+        ThroughputInfo {
+            interfaces: Vec::new(),
+        }
+    }
+
+    // measure `interface`, every interface that is up but loopback
+    // for "all", or the default route's interfaces (all, without one) for None.
+    pub fn get(&mut self, interface: Option<&str>, interval: Duration) -> Result<()> {
+        let before = read_counters()?;
+        let start = Instant::now();
+        thread::sleep(interval);
+        let after = read_counters()?;
+        let elapsed = start.elapsed().as_secs_f64();
+
+        let names = match interface {
+            Some("all") => Vec::new(),
+            Some(name) => {
+                ensure!(
+                    after.iter().any(|(n, _, _)| n == name),
+                    NoInterface {
+                        name: name.to_string()
+                    }
+                );
+                vec![name.to_string()]
+            }
+            None => default_interfaces(),
+        };
+
+        for (name, rx, tx) in after {
+            let wanted = if names.is_empty() {
+                name != "lo" && is_up(&name)
+            } else {
+                names.contains(&name)
+            };
+            if !wanted {
+                continue;
+            }
+
+            // counters of interfaces that went away or were reset count from 0
+            let (rx_before, tx_before) = match before.iter().find(|(n, _, _)| n == &name) {
+                Some((_, r, t)) if *r <= rx && *t <= tx => (*r, *t),
+                _ => (rx, tx),
+            };
+
+            self.interfaces.push(Throughput {
+                interface: name,
+                rx_rate: (rx - rx_before) as f64 / elapsed,
+                tx_rate: (tx - tx_before) as f64 / elapsed,
+                rx_total: rx,
+                tx_total: tx,
+            });
+        }
+
+        Ok(())
+    }
+
+    // format it, one interface per line:
+    // `eth0: rx 1.2MiB/s, tx 30.5KiB/s (rx 3.4GiB, tx 512.0MiB since boot)`
+    pub fn format(&self) -> String {
+        if self.interfaces.is_empty() {
+            return "none".to_string();
+        }
+
+        self.interfaces
+            .iter()
+            .map(|t| {
+                format!(
+                    "{}: rx {}/s, tx {}/s (rx {}, tx {} since boot)",
+                    t.interface,
+                    human_bytes(t.rx_rate),
+                    human_bytes(t.tx_rate),
+                    human_bytes(t.rx_total as f64),
+                    human_bytes(t.tx_total as f64)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+fn is_up(name: &str) -> bool {
+    match fs::read_to_string(format!("/sys/class/net/{}/operstate", name)) {
+        Ok(state) => state.trim() != "down",
+        Err(_) => true,
+    }
+}
+
+// the received and sent bytes of every interface.
+fn read_counters() -> Result<Vec<(String, u64, u64)>> {
+    let dev = fs::read_to_string("/proc/net/dev").context(ReadNetDev)?;
+    Ok(parse_counters(&dev))
+}
+
+// the counters in /proc/net/dev: after two header lines, `name: ` and 8
+// receive then 8 transmit counters, the bytes being the first of each
+fn parse_counters(dev: &str) -> Vec<(String, u64, u64)> {
+    let mut counters = Vec::new();
+    for line in dev.lines().skip(2) {
+        if let Some((name, stats)) = line.split_once(':') {
+            let stats = stats
+                .split_whitespace()
+                .map(|s| s.parse::<u64>().unwrap_or(0))
+                .collect::<Vec<u64>>();
+            if stats.len() > 8 {
+                counters.push((name.trim().to_string(), stats[0], stats[8]));
+            }
+        }
+    }
+    counters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing() {
+        let dev = "Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:  112204    1056    0    0    0     0          0         0   112204    1056    0    0    0     0       0          0
+  eth0:33482715   24211    0    0    0     0          0         0   235893    2920    0    0    0     0       0          0
+wlp3s0: 18446744073709551615 12 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+ short: 1 2 3
+";
+        assert_eq!(
+            parse_counters(dev),
+            vec![
+                ("lo".to_string(), 112204, 112204),
+                ("eth0".to_string(), 33482715, 235893),
+                ("wlp3s0".to_string(), u64::MAX, 0),
+            ]
+        );
+        assert!(parse_counters("").is_empty());
+    }
+
+    #[test]
+    fn formatting() {
+        let mut throughput = ThroughputInfo::new();
+        assert_eq!(throughput.format(), "none");
+
+        throughput.interfaces.push(Throughput {
+            interface: "eth0".to_string(),
+            rx_rate: 1258291.2,
+            tx_rate: 31232_f64,
+            rx_total: 3650722202,
+            tx_total: 536870912,
+        });
+        assert_eq!(
+            throughput.format(),
+            "eth0: rx 1.2MiB/s, tx 30.5KiB/s (rx 3.4GiB, tx 512.0MiB since boot)"
+        );
+    }
+}
\ No newline at end of file
@@ -21,7 +21,7 @@ impl UptimeInfo {
     pub fn get(&mut self) -> Result<()> {
         let seconds: u64;
         if fs::metadata("/proc/uptime").is_ok() {
-            let mut proc_uptime: &str = &*fs::read_to_string("/proc/uptime").context(Uptime)?;
+            let mut proc_uptime: &str = &fs::read_to_string("/proc/uptime").context(Uptime)?;
 
             // right now, proc_uptime looks like this:
             // 98798798.98 12897928l.12
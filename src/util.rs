@@ -1,5 +1,6 @@
 // various utility functions
 use crate::*;
+use libc::{c_int, ioctl, isatty, winsize, STDOUT_FILENO, TIOCGWINSZ};
 use std::process::Command;
 
 #[derive(Clone, PartialEq)]
@@ -26,7 +27,7 @@ impl OSInfo {
             .stdout
             .iter()
             .for_each(|b| uname.push(*b as char));
-        let os = match uname.replace("\n", "").trim().as_ref() {
+        let os = match uname.replace("\n", "").trim() {
             "Linux" => OS::Linux,
             "FreeBSD" => OS::FreeBSD,
             "NetBSD" => OS::NetBSD,
@@ -38,3 +39,59 @@ impl OSInfo {
         Ok(os)
     }
 }
+
+// whether stdout is a terminal, as opposed to a pipe or a file.
+pub fn stdout_is_tty() -> bool {
+    unsafe { isatty(1 as c_int) != 0 }
+}
+
+// the size of the terminal stdout is connected to.
+fn window_size() -> Option<winsize> {
+    let mut size = winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    let ret = unsafe { ioctl(STDOUT_FILENO, TIOCGWINSZ, &mut size) };
+    if ret == 0 && size.ws_col > 0 {
+        Some(size)
+    } else {
+        None
+    }
+}
+
+// the number of columns of the terminal stdout is connected to.
+pub fn terminal_width() -> Option<usize> {
+    window_size().map(|size| size.ws_col as usize)
+}
+
+// the width and height of a terminal cell in pixels,
+// if the terminal lets us know.
+pub fn cell_size() -> Option<(u32, u32)> {
+    match window_size() {
+        Some(size) if size.ws_xpixel > 0 && size.ws_ypixel > 0 && size.ws_row > 0 => Some((
+            (size.ws_xpixel / size.ws_col) as u32,
+            (size.ws_ypixel / size.ws_row) as u32,
+        )),
+        _ => None,
+    }
+}
+
+// a number of bytes in the largest unit that keeps it above 1, e.g. `3.4GiB`.
+pub fn human_bytes(bytes: f64) -> String {
+    let units = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024_f64 {
+        return format!("{:.0}B", bytes);
+    }
+
+    let mut value = bytes / 1024_f64;
+    let mut unit = 0;
+    while value >= 1024_f64 && unit < units.len() - 1 {
+        value /= 1024_f64;
+        unit += 1;
+    }
+
+    format!("{:.1}{}", value, units[unit])
+}
@@ -0,0 +1,171 @@
+// the VPNs and other tunnels that are up, and whether
+// the traffic to the internet goes through one of them.
+use crate::*;
+use std::fs;
+use std::net::{IpAddr, UdpSocket};
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// link types, see linux/if_arp.h
+const ARPHRD_PPP: u32 = 512;
+// tun_flags, see linux/if_tun.h
+const IFF_TAP: u32 = 0x0002;
+
+pub struct Tunnel {
+    pub interface: String,
+    // "WireGuard", "tun", "tap" or "PPP"
+    pub kind: &'static str,
+    pub addresses: Vec<(IpAddr, u32)>,
+    // the number of WireGuard peers, and of those with a recent handshake,
+    // if `wg` can tell (which usually takes root)
+    pub peers: Option<(usize, usize)>,
+    // whether the traffic to the internet goes through it
+    pub routed: bool,
+}
+
+pub struct VpnInfo {
+    pub tunnels: Vec<Tunnel>,
+}
+
+impl VpnInfo {
+    pub fn new() -> VpnInfo {
+        VpnInfo {
+            tunnels: Vec::new(),
+        }
+    }
+
+    pub fn get(&mut self) -> Result<()> {
+        let mut interfaces = InterfaceInfo::new();
+        interfaces.get(false)?;
+
+        let routed = routed_addresses();
+        for interface in interfaces.interfaces {
+            let kind = match tunnel_kind(&interface.name) {
+                Some(k) => k,
+                None => continue,
+            };
+
+            let peers = if kind == "WireGuard" {
+                wireguard_peers(&interface.name)
+            } else {
+                None
+            };
+
+            self.tunnels.push(Tunnel {
+                routed: interface.addresses.iter().any(|(ip, _)| routed.contains(ip)),
+                interface: interface.name,
+                kind,
+                addresses: interface.addresses,
+                peers,
+            });
+        }
+
+        Ok(())
+    }
+
+    // format it, one tunnel per line:
+    // `wg0 (WireGuard): 10.0.0.2/32 - 2 peers, 1 connected (default route)`
+    pub fn format(&self) -> String {
+        if self.tunnels.is_empty() {
+            return "none".to_string();
+        }
+
+        self.tunnels
+            .iter()
+            .map(|t| {
+                let mut details = Vec::new();
+                if !t.addresses.is_empty() {
+                    let addresses = t
+                        .addresses
+                        .iter()
+                        .map(|(ip, prefix)| format!("{}/{}", ip, prefix))
+                        .collect::<Vec<String>>();
+                    details.push(addresses.join(", "));
+                }
+                if let Some((peers, connected)) = t.peers {
+                    let plural = if peers == 1 { "" } else { "s" };
+                    details.push(format!("{} peer{}, {} connected", peers, plural, connected));
+                }
+
+                let mut line = format!("{} ({})", t.interface, t.kind);
+                if !details.is_empty() {
+                    line = format!("{}: {}", line, details.join(" - "));
+                }
+                if t.routed {
+                    line.push_str(" (default route)");
+                }
+                line
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+// what kind of tunnel the interface is, if it is one.
+fn tunnel_kind(name: &str) -> Option<&'static str> {
+    let class = Path::new("/sys/class/net").join(name);
+    let read = |file: &str| fs::read_to_string(class.join(file)).unwrap_or_default();
+
+    if read("uevent").lines().any(|l| l == "DEVTYPE=wireguard") {
+        return Some("WireGuard");
+    }
+
+    // tun and tap devices have their flags in hex, e.g. `0x1002`
+    let tun_flags = read("tun_flags");
+    if let Ok(flags) = u32::from_str_radix(tun_flags.trim().trim_start_matches("0x"), 16) {
+        return Some(if flags & IFF_TAP != 0 { "tap" } else { "tun" });
+    }
+
+    if read("type").trim().parse::<u32>() == Ok(ARPHRD_PPP) {
+        return Some("PPP");
+    }
+
+    // e.g. without sysfs
+    [("wg", "WireGuard"), ("tun", "tun"), ("tap", "tap"), ("ppp", "PPP")]
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, kind)| *kind)
+}
+
+// the local addresses the traffic to the internet would be sent from.
+// connecting a UDP socket sends nothing, but picks the route the kernel
+// would use, policy routing (e.g. wg-quick's) included.
+fn routed_addresses() -> Vec<IpAddr> {
+    ["0.0.0.0:0", "[::]:0"]
+        .iter()
+        .zip(["1.1.1.1:53", "[2606:4700:4700::1111]:53"].iter())
+        .filter_map(|(local, remote)| {
+            let socket = UdpSocket::bind(local).ok()?;
+            socket.connect(remote).ok()?;
+            socket.local_addr().ok().map(|a| a.ip())
+        })
+        .collect()
+}
+
+// the number of peers of a WireGuard interface, and how many of them
+// had a handshake in the last 3 minutes (keys are renewed every 2).
+fn wireguard_peers(name: &str) -> Option<(usize, usize)> {
+    let output = Command::new("wg").args(["show", name, "dump"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+
+    // after the interface's own line, one per peer: public key, preshared key,
+    // endpoint, allowed ips, latest handshake, received, sent, keepalive
+    let dump = String::from_utf8_lossy(&output.stdout).into_owned();
+    let peers = dump
+        .lines()
+        .skip(1)
+        .map(|l| l.split('\t').collect::<Vec<&str>>())
+        .collect::<Vec<Vec<&str>>>();
+    let connected = peers
+        .iter()
+        .filter_map(|p| p.get(4).and_then(|h| h.parse::<u64>().ok()))
+        .filter(|h| *h > 0 && now.saturating_sub(*h) < 180)
+        .count();
+
+    Some((peers.len(), connected))
+}
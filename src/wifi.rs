@@ -0,0 +1,419 @@
+// the wireless interfaces, with the network they are connected to.
+// the link quality comes from /proc/net/wireless, and the SSID,
+// signal and bitrate from the kernel's nl80211 netlink interface.
+use crate::*;
+use std::fs;
+
+pub struct Wireless {
+    pub interface: String,
+    pub ssid: Option<String>,
+    // in dBm
+    pub signal: Option<i32>,
+    // in percent
+    pub quality: Option<f64>,
+    // in Mbit/s, of what's sent
+    pub bitrate: Option<f64>,
+}
+
+pub struct WifiInfo {
+    pub interfaces: Vec<Wireless>,
+}
+
+// what nl80211 tells about the link of an interface
+#[derive(Default)]
+struct Link {
+    ssid: Option<String>,
+    signal: Option<i32>,
+    bitrate: Option<f64>,
+}
+
+impl WifiInfo {
+    pub fn new() -> WifiInfo {
+        WifiInfo {
+            interfaces: Vec::new(),
+        }
+    }
+
+    pub fn get(&mut self) -> Result<()> {
+        let mut names = fs::read_dir("/sys/class/net")
+            .context(ReadWireless)?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().join("wireless").exists() || e.path().join("phy80211").exists())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect::<Vec<String>>();
+        names.sort();
+
+        let proc = parse_wireless(&fs::read_to_string("/proc/net/wireless").unwrap_or_default());
+
+        for name in names {
+            let mut wireless = Wireless {
+                interface: name,
+                ssid: None,
+                signal: None,
+                quality: None,
+                bitrate: None,
+            };
+
+            if let Some((_, quality, signal)) = proc.iter().find(|(i, _, _)| *i == wireless.interface) {
+                wireless.quality = *quality;
+                wireless.signal = *signal;
+            }
+
+            // drivers without nl80211 only have what /proc/net/wireless says
+            if let Ok(link) = nl80211::link(&wireless.interface) {
+                wireless.ssid = link.ssid;
+                wireless.signal = link.signal.or(wireless.signal);
+                wireless.bitrate = link.bitrate;
+            }
+
+            self.interfaces.push(wireless);
+        }
+
+        Ok(())
+    }
+
+    // format it, one interface per line:
+    // `wlan0: MyNetwork - -52dBm (quality 83%) - 866.7Mbit/s`
+    pub fn format(&self) -> String {
+        if self.interfaces.is_empty() {
+            return "none".to_string();
+        }
+
+        self.interfaces
+            .iter()
+            .map(|w| {
+                if w.ssid.is_none() && w.quality.is_none() {
+                    return format!("{}: disconnected", w.interface);
+                }
+
+                let mut details = Vec::new();
+                if let Some(ssid) = &w.ssid {
+                    details.push(ssid.clone());
+                }
+                match (w.signal, w.quality) {
+                    (Some(signal), Some(quality)) => details.push(format!("{}dBm (quality {:.0}%)", signal, quality)),
+                    (Some(signal), None) => details.push(format!("{}dBm", signal)),
+                    (None, Some(quality)) => details.push(format!("quality {:.0}%", quality)),
+                    (None, None) => (),
+                }
+                if let Some(bitrate) = w.bitrate {
+                    details.push(format!("{:.1}Mbit/s", bitrate));
+                }
+
+                format!("{}: {}", w.interface, details.join(" - "))
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+// the link quality (in percent) and the signal (in dBm) of each interface
+// in /proc/net/wireless, which has two header lines, then:
+// interface: status, link quality, signal level, noise level, ...
+fn parse_wireless(table: &str) -> Vec<(String, Option<f64>, Option<i32>)> {
+    let mut links = Vec::new();
+    for line in table.lines().skip(2) {
+        if let Some((iface, stats)) = line.split_once(':') {
+            let stats = stats
+                .split_whitespace()
+                .map(|s| s.trim_end_matches('.').parse::<f64>().ok())
+                .collect::<Vec<Option<f64>>>();
+            if stats.len() > 2 {
+                // the quality is out of 70 for most drivers, and some
+                // give the signal level as an unsigned byte
+                let quality = stats[1].map(|q| (q / 70_f64 * 100_f64).min(100_f64));
+                let signal = stats[2]
+                    .map(|l| if l > 63_f64 { l - 256_f64 } else { l } as i32)
+                    .filter(|l| *l != 0);
+                links.push((iface.trim().to_string(), quality, signal));
+            }
+        }
+    }
+    links
+}
+
+#[cfg(target_os = "linux")]
+mod nl80211 {
+    use super::Link;
+    use libc::{c_int, c_void, sockaddr, sockaddr_nl, socklen_t, timeval};
+    use libc::{AF_NETLINK, NETLINK_GENERIC, NLMSG_DONE, NLMSG_ERROR, NLM_F_DUMP, NLM_F_REQUEST};
+    use libc::{SOCK_CLOEXEC, SOCK_RAW, SOL_SOCKET, SO_RCVTIMEO};
+    use std::ffi::CString;
+    use std::io;
+    use std::mem;
+
+    // generic netlink's controller, which knows the families' ids
+    const GENL_ID_CTRL: u16 = 0x10;
+    const CTRL_CMD_GETFAMILY: u8 = 3;
+    const CTRL_ATTR_FAMILY_ID: u16 = 1;
+    const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+
+    // see linux/nl80211.h
+    const NL80211_CMD_GET_INTERFACE: u8 = 5;
+    const NL80211_CMD_GET_STATION: u8 = 17;
+    const NL80211_ATTR_IFINDEX: u16 = 3;
+    const NL80211_ATTR_STA_INFO: u16 = 21;
+    const NL80211_ATTR_SSID: u16 = 52;
+    const NL80211_STA_INFO_SIGNAL: u16 = 7;
+    const NL80211_STA_INFO_TX_BITRATE: u16 = 8;
+    const NL80211_RATE_INFO_BITRATE: u16 = 1;
+    const NL80211_RATE_INFO_BITRATE32: u16 = 5;
+
+    // netlink message and generic netlink headers
+    const HEADER: usize = 16 + 4;
+
+    struct Socket {
+        fd: c_int,
+        seq: u32,
+    }
+
+    impl Drop for Socket {
+        fn drop(&mut self) {
+            unsafe { libc::close(self.fd) };
+        }
+    }
+
+    impl Socket {
+        fn open() -> io::Result<Socket> {
+            let fd = unsafe { libc::socket(AF_NETLINK, SOCK_RAW | SOCK_CLOEXEC, NETLINK_GENERIC) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let socket = Socket { fd, seq: 0 };
+
+            let timeout = timeval { tv_sec: 1, tv_usec: 0 };
+            let ret = unsafe {
+                libc::setsockopt(
+                    fd,
+                    SOL_SOCKET,
+                    SO_RCVTIMEO,
+                    &timeout as *const timeval as *const c_void,
+                    mem::size_of::<timeval>() as socklen_t,
+                )
+            };
+            if ret < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(socket)
+        }
+
+        // send a command, and return the attributes of each message of the
+        // answer. dumps are answered with several, up to NLMSG_DONE.
+        fn request(&mut self, family: u16, command: u8, attrs: &[(u16, &[u8])], dump: bool) -> io::Result<Vec<Vec<u8>>> {
+            self.seq += 1;
+            let flags = if dump { NLM_F_REQUEST | NLM_F_DUMP } else { NLM_F_REQUEST };
+
+            let mut msg = Vec::new();
+            msg.extend_from_slice(&0_u32.to_ne_bytes());
+            msg.extend_from_slice(&family.to_ne_bytes());
+            msg.extend_from_slice(&(flags as u16).to_ne_bytes());
+            msg.extend_from_slice(&self.seq.to_ne_bytes());
+            msg.extend_from_slice(&0_u32.to_ne_bytes());
+            msg.extend_from_slice(&[command, 1, 0, 0]);
+            for (kind, value) in attrs {
+                msg.extend_from_slice(&((4 + value.len()) as u16).to_ne_bytes());
+                msg.extend_from_slice(&kind.to_ne_bytes());
+                msg.extend_from_slice(value);
+                msg.resize(align(msg.len()), 0);
+            }
+            let len = (msg.len() as u32).to_ne_bytes();
+            msg[..4].copy_from_slice(&len);
+
+            let mut kernel: sockaddr_nl = unsafe { mem::zeroed() };
+            kernel.nl_family = AF_NETLINK as u16;
+            let sent = unsafe {
+                libc::sendto(
+                    self.fd,
+                    msg.as_ptr() as *const c_void,
+                    msg.len(),
+                    0,
+                    &kernel as *const sockaddr_nl as *const sockaddr,
+                    mem::size_of::<sockaddr_nl>() as socklen_t,
+                )
+            };
+            if sent < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut answers = Vec::new();
+            let mut buf = vec![0_u8; 32768];
+            loop {
+                let received = unsafe { libc::recv(self.fd, buf.as_mut_ptr() as *mut c_void, buf.len(), 0) };
+                if received < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+
+                let mut data = &buf[..received as usize];
+                while data.len() >= 16 {
+                    let len = u32::from_ne_bytes([data[0], data[1], data[2], data[3]]) as usize;
+                    let kind = u16::from_ne_bytes([data[4], data[5]]) as c_int;
+                    if len < 16 || len > data.len() {
+                        break;
+                    }
+
+                    match kind {
+                        NLMSG_DONE => return Ok(answers),
+                        NLMSG_ERROR => {
+                            let code = i32::from_ne_bytes([data[16], data[17], data[18], data[19]]);
+                            if code != 0 {
+                                return Err(io::Error::from_raw_os_error(-code));
+                            }
+                            return Ok(answers);
+                        }
+                        _ if len >= HEADER => answers.push(data[HEADER..len].to_vec()),
+                        _ => (),
+                    }
+
+                    data = &data[align(len).min(data.len())..];
+                }
+
+                if !dump && !answers.is_empty() {
+                    return Ok(answers);
+                }
+            }
+        }
+    }
+
+    fn align(len: usize) -> usize {
+        (len + 3) & !3
+    }
+
+    // the type and value of each attribute in `data`.
+    fn attributes(mut data: &[u8]) -> Vec<(u16, &[u8])> {
+        let mut attrs = Vec::new();
+        while data.len() >= 4 {
+            let len = u16::from_ne_bytes([data[0], data[1]]) as usize;
+            // without the nested and byte order flags
+            let kind = u16::from_ne_bytes([data[2], data[3]]) & 0x3FFF;
+            if len < 4 || len > data.len() {
+                break;
+            }
+            attrs.push((kind, &data[4..len]));
+            data = &data[align(len).min(data.len())..];
+        }
+        attrs
+    }
+
+    fn find(attrs: &[(u16, &[u8])], kind: u16) -> Option<Vec<u8>> {
+        attrs.iter().find(|(k, _)| *k == kind).map(|(_, v)| v.to_vec())
+    }
+
+    pub(super) fn link(interface: &str) -> io::Result<Link> {
+        let name = CString::new(interface).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        if index == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let index = index.to_ne_bytes();
+
+        let mut socket = Socket::open()?;
+        let answer = socket.request(
+            GENL_ID_CTRL,
+            CTRL_CMD_GETFAMILY,
+            &[(CTRL_ATTR_FAMILY_NAME, b"nl80211\0")],
+            false,
+        )?;
+        let family = answer
+            .first()
+            .and_then(|a| find(&attributes(a), CTRL_ATTR_FAMILY_ID))
+            .filter(|id| id.len() >= 2)
+            .map(|id| u16::from_ne_bytes([id[0], id[1]]))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no nl80211"))?;
+
+        let mut link = Link::default();
+
+        let answer = socket.request(family, NL80211_CMD_GET_INTERFACE, &[(NL80211_ATTR_IFINDEX, &index)], false)?;
+        if let Some(ssid) = answer.first().and_then(|a| find(&attributes(a), NL80211_ATTR_SSID)) {
+            link.ssid = Some(String::from_utf8_lossy(&ssid).into_owned());
+        }
+
+        // when connected, the access point is the only station
+        let answer = socket.request(family, NL80211_CMD_GET_STATION, &[(NL80211_ATTR_IFINDEX, &index)], true)?;
+        if let Some(info) = answer.first().and_then(|a| find(&attributes(a), NL80211_ATTR_STA_INFO)) {
+            let info = attributes(&info);
+            link.signal = find(&info, NL80211_STA_INFO_SIGNAL)
+                .and_then(|s| s.first().copied())
+                .map(|s| s as i8 as i32);
+
+            // in units of 100kbit/s
+            if let Some(rate) = find(&info, NL80211_STA_INFO_TX_BITRATE) {
+                let rate = attributes(&rate);
+                let bitrate = match (find(&rate, NL80211_RATE_INFO_BITRATE32), find(&rate, NL80211_RATE_INFO_BITRATE)) {
+                    (Some(b), _) if b.len() >= 4 => Some(u32::from_ne_bytes([b[0], b[1], b[2], b[3]])),
+                    (_, Some(b)) if b.len() >= 2 => Some(u16::from_ne_bytes([b[0], b[1]]) as u32),
+                    _ => None,
+                };
+                link.bitrate = bitrate.filter(|b| *b > 0).map(|b| b as f64 / 10_f64);
+            }
+        }
+
+        Ok(link)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod nl80211 {
+    use super::Link;
+    use std::io;
+
+    pub(super) fn link(_interface: &str) -> io::Result<Link> {
+        Err(io::Error::new(io::ErrorKind::Other, "nl80211 is Linux only"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing() {
+        let table = "Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE
+ face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22
+wlp3s0: 0000   58.  -52.  -256        0      0      0      0     42        0
+ wlan1: 0000   80.  204.     0        0      0      0      0      0        0
+ wlan2: 0000    0.    0.     0        0      0      0      0      0        0
+";
+        let links = parse_wireless(table);
+        assert_eq!(links.len(), 3);
+
+        let (interface, quality, signal) = &links[0];
+        assert_eq!(interface, "wlp3s0");
+        assert_eq!(quality.map(|q| q.round()), Some(83_f64));
+        assert_eq!(*signal, Some(-52));
+
+        // an unsigned signal level, and a quality over 70
+        assert_eq!(links[1], ("wlan1".to_string(), Some(100_f64), Some(-52)));
+        // no signal at all
+        assert_eq!(links[2], ("wlan2".to_string(), Some(0_f64), None));
+
+        assert!(parse_wireless("").is_empty());
+    }
+
+    #[test]
+    fn formatting() {
+        let mut wifi = WifiInfo::new();
+        assert_eq!(wifi.format(), "none");
+
+        wifi.interfaces = vec![
+            Wireless {
+                interface: "wlan0".to_string(),
+                ssid: Some("MyNetwork".to_string()),
+                signal: Some(-52),
+                quality: Some(82.9),
+                bitrate: Some(866.7),
+            },
+            Wireless {
+                interface: "wlan1".to_string(),
+                ssid: None,
+                signal: None,
+                quality: None,
+                bitrate: None,
+            },
+        ];
+        assert_eq!(
+            wifi.format(),
+            "wlan0: MyNetwork - -52dBm (quality 83%) - 866.7Mbit/s\nwlan1: disconnected"
+        );
+    }
+}
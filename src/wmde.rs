@@ -20,9 +20,8 @@ impl WMDEInfo {
             .or_else(|_| env::var("XDG_CURRENT_DESKTOP"))
             .or_else(|_| env::var("DESKTOP_SESSION"));
 
-        match de {
-            Ok(d) => self.de = d,
-            Err(_) => (),
+        if let Ok(d) = de {
+            self.de = d;
         }
 
         // if Err() is returned anywhere, it will be returned right
@@ -39,7 +38,7 @@ impl WMDEInfo {
             .context(EmptyXInitRc)?
             .context(ReadXInitRc)?;
 
-        if let Some(wm) = last_line.split(' ').last() {
+        if let Some(wm) = last_line.split(' ').next_back() {
             self.wm = wm.trim().to_string();
         } else {
             self.wm = "?".to_string();
@@ -50,7 +49,7 @@ impl WMDEInfo {
 
     // format it
     pub fn format(&self) -> String {
-        if self.de != "" {
+        if !self.de.is_empty() {
             self.de.clone()
         } else {
             self.wm.clone()